@@ -4,10 +4,10 @@
 #include <ostream>
 #include <new>
 
-struct BenchmarkDescriptor;
-
 struct MapfBenchmarker;
 
+struct BenchmarkDescriptor;
+
 struct Path;
 
 struct Problem;
@@ -26,7 +26,8 @@ struct MarkedCoordinate {
 
 extern "C" {
 
-/// Create a new benchmarker (note: should be freed with free_benchmarker)
+/// Create a new benchmarker (note: should be freed with free_benchmarker). The solver may return null for problems it cannot solve.
+/// The solver is called for one problem at a time, on the thread calling run_benchmark, so it doesn't need to be thread-safe
 const MapfBenchmarker *create_benchmarker(const char *token,
                                           BenchmarkDescriptor *benchmark,
                                           const char *algorithm_name,
@@ -0,0 +1,112 @@
+use crate::client::{BenchmarkDescriptor, SerializedProblem};
+use crate::problem::Problem;
+use crate::runner::SolveResult;
+use crate::solver::SolverOutcome;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{fs, io};
+
+#[derive(Serialize)]
+struct SolutionArtifact<'a> {
+    outcome: &'a SolverOutcome,
+    /// in nanoseconds
+    time: u64,
+}
+
+/// The directory the artifacts of a single attempt (or a run without an attempt) are
+/// written to, inside the artifact directory of a benchmarker:
+///
+/// - `problems/<index>.json`: every problem, in the format the server sends them in,
+///   so the directory can be solved again with [`run_local`](crate::MapfBenchmarker::run_local).
+/// - `solutions/<index>.json`: what the solver made of every problem, and how long it took.
+/// - `submissions/<n>.json`: the bodies sent to the server, in the order they were sent.
+pub(crate) struct Artifacts {
+    dir: PathBuf,
+}
+
+impl Artifacts {
+    /// The directory for `attempt_id` is the same every time, so an attempt which is
+    /// resumed adds to it. Runs without an attempt get a directory of their own.
+    pub fn new(root: &Path, descriptor: &BenchmarkDescriptor, attempt_id: Option<i64>) -> Self {
+        let run = match attempt_id {
+            Some(id) => format!("attempt-{}", id),
+            None => format!("run-{}", SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis()),
+        };
+
+        Self {
+            dir: root.join(descriptor.name()).join(run),
+        }
+    }
+
+    /// Writes the problems, before they are solved, so they are kept even when solving
+    /// takes the process down.
+    pub fn write_problems(&self, problems: &[Problem]) -> io::Result<()> {
+        let dir = self.dir.join("problems");
+        fs::create_dir_all(&dir)?;
+
+        for (index, problem) in problems.iter().enumerate() {
+            fs::write(dir.join(file_name(index)), serde_json::to_vec_pretty(&SerializedProblem::from_problem(problem))?)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the result for the problem at `index`. Like the solutions and submissions,
+    /// these are only debugging aids, so failing to write them doesn't fail the run.
+    pub fn write_result(&self, index: usize, (outcome, _, time): &SolveResult) {
+        let artifact = SolutionArtifact {
+            outcome,
+            time: time.as_nanos() as u64,
+        };
+
+        let write = || -> io::Result<()> {
+            let dir = self.dir.join("solutions");
+            fs::create_dir_all(&dir)?;
+            fs::write(dir.join(file_name(index)), serde_json::to_vec_pretty(&artifact)?)
+        };
+        let _ = write();
+    }
+
+    pub fn write_submission(&self, body: &[u8]) {
+        let write = || -> io::Result<()> {
+            let dir = self.dir.join("submissions");
+            fs::create_dir_all(&dir)?;
+            fs::write(dir.join(file_name(fs::read_dir(&dir)?.count())), body)
+        };
+        let _ = write();
+    }
+}
+
+/// Zero padded, so ordering the files by name orders them by index.
+fn file_name(index: usize) -> String {
+    format!("{:05}.json", index)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::artifacts::Artifacts;
+    use crate::client::BenchmarkDescriptor;
+    use crate::local::load_problems;
+    use crate::solver::SolverOutcome;
+    use crate::testing::{problem, TempPath};
+    use std::fs;
+    use std::time::Duration;
+
+    #[test]
+    pub fn test_artifacts() {
+        let root = TempPath::new("artifacts");
+        let problems: Vec<_> = (1..=2).map(problem).collect();
+
+        let artifacts = Artifacts::new(&root, &BenchmarkDescriptor::from_identifier(3), Some(7));
+        artifacts.write_problems(&problems).unwrap();
+        artifacts.write_result(1, &(SolverOutcome::Unsolvable, problems[1].clone(), Duration::from_millis(1)));
+        artifacts.write_submission(b"{}");
+        artifacts.write_submission(b"[]");
+
+        let dir = root.join("3").join("attempt-7");
+        assert_eq!(load_problems(&dir.join("problems")).unwrap(), problems);
+        assert!(fs::read_to_string(dir.join("solutions/00001.json")).unwrap().contains("Unsolvable"));
+        assert_eq!(fs::read_to_string(dir.join("submissions/00001.json")).unwrap(), "[]");
+    }
+}
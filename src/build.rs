@@ -13,6 +13,10 @@ fn main() {
 
     cbindgen::Builder::new()
         .with_crate(crate_dir)
+        // MapfBenchmarker is generic over its transport, which would otherwise be exported as a template.
+        // C only ever sees it behind a pointer, so an opaque declaration is enough.
+        .exclude_item("MapfBenchmarker")
+        .with_after_include("\nstruct MapfBenchmarker;")
         .generate()
         .expect("Unable to generate bindings")
         .write_to_file("bindings.h");
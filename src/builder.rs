@@ -0,0 +1,356 @@
+use crate::cache::BenchmarkCache;
+use crate::client::{BenchmarkDescriptor, ErrorPolicy, MapfBenchmarker, MapfmClientError};
+use crate::http::HttpTransport;
+use crate::http_config::HttpConfig;
+use crate::local::Offline;
+use crate::observer::{Observer, Observers};
+use crate::retry::RetryPolicy;
+use crate::runner::TimeLimits;
+use crate::solver::SharedSolver;
+use crate::token::Token;
+use crate::transport::Transport;
+use reqwest::Url;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The server benchmarks are run against when no other base url is given.
+pub const DEFAULT_BASE_URL: &str = "https://mapf.nl/";
+
+/// How a benchmarker solves and submits, whatever its transport.
+pub(crate) struct RunOptions {
+    pub token_validation: bool,
+    pub time_limits: TimeLimits,
+    pub failure_cutoff: Option<usize>,
+    pub error_policy: ErrorPolicy,
+    pub workers: usize,
+    pub stream_batch_size: Option<usize>,
+    pub checkpoint_path: Option<PathBuf>,
+    pub artifacts_path: Option<PathBuf>,
+    pub observers: Observers,
+}
+
+impl Default for RunOptions {
+    fn default() -> Self {
+        Self {
+            token_validation: false,
+            time_limits: TimeLimits::default(),
+            failure_cutoff: None,
+            error_policy: ErrorPolicy::default(),
+            workers: 1,
+            stream_batch_size: None,
+            checkpoint_path: None,
+            artifacts_path: None,
+            observers: Vec::new(),
+        }
+    }
+}
+
+/// Sets up a [`MapfBenchmarker`], see [`MapfBenchmarker::builder`]. Nothing is checked
+/// until [`build`](Self::build), which fails on a setup that could never run.
+pub struct MapfBenchmarkerBuilder {
+    algorithm_name: String,
+    solver: SharedSolver,
+    benchmarks: Vec<BenchmarkDescriptor>,
+    version: String,
+    debug: bool,
+    token: Option<Token>,
+    base_url: String,
+
+    retry_policy: Option<RetryPolicy>,
+    cache: Option<BenchmarkCache>,
+    http_config: Option<HttpConfig>,
+    recording: Option<PathBuf>,
+
+    options: RunOptions,
+}
+
+impl MapfBenchmarkerBuilder {
+    pub(crate) fn new(algorithm_name: &str, solver: SharedSolver) -> Self {
+        Self {
+            algorithm_name: algorithm_name.to_string(),
+            solver,
+            benchmarks: Vec::new(),
+            version: "1".to_string(),
+            debug: true,
+            token: None,
+            base_url: DEFAULT_BASE_URL.to_string(),
+
+            retry_policy: None,
+            cache: None,
+            http_config: None,
+            recording: None,
+
+            options: RunOptions::default(),
+        }
+    }
+
+    /// Adds a benchmark to run. Benchmarks are run in the order they were added.
+    pub fn with_benchmark(mut self, benchmark: BenchmarkDescriptor) -> Self {
+        self.benchmarks.push(benchmark);
+        self
+    }
+
+    pub fn with_benchmarks(mut self, benchmarks: impl IntoIterator<Item = BenchmarkDescriptor>) -> Self {
+        self.benchmarks.extend(benchmarks);
+        self
+    }
+
+    /// The version of the algorithm attempts are made with. Defaults to `1`.
+    pub fn with_version(mut self, version: &str) -> Self {
+        self.version = version.to_string();
+        self
+    }
+
+    /// Whether attempts are debug attempts, which don't show up on the leaderboards.
+    /// Defaults to `true`, so an attempt only counts when that is asked for.
+    pub fn with_debug(mut self, debug: bool) -> Self {
+        self.debug = debug;
+        self
+    }
+
+    /// The token to authenticate with. When none is given, it is taken from the
+    /// environment or the config file, see [`Token::resolve`].
+    pub fn with_token(mut self, token: impl Into<Token>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Whether to check the token is accepted before running the benchmarks, see
+    /// [`MapfBenchmarker::validate_token`].
+    ///
+    /// Off by default. The server has no request for only checking a token, so validating
+    /// downloads the problems of the first benchmark an extra time. And without it, a wrong
+    /// token already fails a run at its first request, which fetches the problems, before
+    /// anything is solved. It only saves work when resuming an attempt, which solves the
+    /// remaining problems before it talks to the server.
+    pub fn with_token_validation(mut self, validate: bool) -> Self {
+        self.options.token_validation = validate;
+        self
+    }
+
+    /// The server to talk to. Defaults to [`DEFAULT_BASE_URL`].
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.to_string();
+        self
+    }
+
+    /// Retries requests to the server which failed with a transient error.
+    /// See [`HttpTransport::with_retry_policy`].
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    /// Caches the problems of benchmarks in `cache`. See [`HttpTransport::with_cache`],
+    /// and [`build`](Self::build) for running from the cache without a token.
+    pub fn with_cache(mut self, cache: BenchmarkCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Configures the http clients used to talk to the server. Anything invalid in
+    /// `config`, like the url of the proxy, makes [`build`](Self::build) fail.
+    pub fn with_http_config(mut self, config: &HttpConfig) -> Self {
+        self.http_config = Some(config.clone());
+        self
+    }
+
+    /// Records the session with the server into a cassette file at `path`.
+    /// See [`HttpTransport::with_recording`].
+    pub fn with_recording(mut self, path: impl AsRef<Path>) -> Self {
+        self.recording = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Sets the wall-clock time the solver gets for a single problem. Problems which
+    /// aren't solved in time are submitted as unsolved, and the run moves on to the
+    /// next problem.
+    ///
+    /// Note that a solver can't be forcibly stopped: it is left to run in the
+    /// background until it returns by itself.
+    pub fn with_problem_time_limit(mut self, limit: Duration) -> Self {
+        self.options.time_limits.problem = Some(limit);
+        self
+    }
+
+    /// Sets the wall-clock time the solver gets for all problems of a benchmark
+    /// together. Once it is used up, the remaining problems are submitted as unsolved.
+    pub fn with_benchmark_time_limit(mut self, limit: Duration) -> Self {
+        self.options.time_limits.benchmark = Some(limit);
+        self
+    }
+
+    /// Stops escalating once the solver starts failing: after it didn't solve `failures`
+    /// problems in a row, the remaining problems with more agents than the fewest it failed
+    /// on among those are skipped. This is meant for progressive benchmarks, which go from few to
+    /// many agents. Skipped problems are submitted as unsolved.
+    pub fn with_failure_cutoff(mut self, failures: usize) -> Self {
+        self.options.failure_cutoff = Some(failures.max(1));
+        self
+    }
+
+    /// Whether to keep running the other benchmarks when one fails.
+    /// Defaults to [`ErrorPolicy::FailFast`]. See [`MapfBenchmarker::run_each`].
+    pub fn with_error_policy(mut self, policy: ErrorPolicy) -> Self {
+        self.options.error_policy = policy;
+        self
+    }
+
+    /// Solves up to `workers` problems of a benchmark at the same time, each on its own
+    /// thread. Solutions are still submitted in the original order of the problems.
+    /// Defaults to 1, solving problems one after another.
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        self.options.workers = workers.max(1);
+        self
+    }
+
+    /// Submits solutions in batches of `batch_size` while the benchmark is still being
+    /// solved, instead of all at once at the end. A batch the server didn't get (because it
+    /// couldn't be reached, or was too busy) is sent again along with the next one; any other
+    /// failure, including a timeout, stops submitting and fails the run. See
+    /// [`MapfBenchmarker::acknowledged`] for which problems have been submitted so far.
+    ///
+    /// **Experimental:** over http, see [`HttpTransport`]'s
+    /// [`submit_batch`](crate::SolutionSink::submit_batch).
+    pub fn with_streaming_submission(mut self, batch_size: usize) -> Self {
+        self.options.stream_batch_size = Some(batch_size.max(1));
+        self
+    }
+
+    /// Keeps a state file in the directory `dir` while an attempt is running, with the
+    /// attempt id, its problems and the solutions found so far. Every benchmark gets a file
+    /// of its own, see [`MapfBenchmarker::checkpoint_file`]. When the process is stopped
+    /// before the attempt is submitted, [`MapfBenchmarker::resume`] can finish it from that
+    /// file. The file is removed once the attempt has been submitted.
+    pub fn with_checkpoint(mut self, dir: impl AsRef<Path>) -> Self {
+        self.options.checkpoint_path = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Writes every problem received, what the solver made of it, how long that took and
+    /// every submission sent to the server to files in `dir`, so failing problems can be
+    /// looked into afterwards. Every attempt gets a directory of its own, named after the
+    /// benchmark and the attempt id, with a `problems` directory which can be solved again
+    /// with [`MapfBenchmarker::run_local`].
+    pub fn with_artifacts(mut self, dir: impl AsRef<Path>) -> Self {
+        self.options.artifacts_path = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Sends every [`Event`](crate::Event) of a run to `observer`, on top of the observers added before.
+    pub fn with_observer(mut self, observer: impl Observer + 'static) -> Self {
+        self.options.observers.push(Arc::new(observer));
+        self
+    }
+
+    /// A benchmarker talking to the server over http. Fails when there are no benchmarks,
+    /// the base url or http config is invalid, or no token was given or found. Only with a
+    /// cache which is [preferred](BenchmarkCache::prefer_cache) no token is needed: then
+    /// cached benchmarks can be run without attempts, and anything which has to go to the
+    /// server fails with [`MissingToken`](MapfmClientError::MissingToken).
+    pub fn build(mut self) -> Result<MapfBenchmarker, MapfmClientError> {
+        check_base_url(&self.base_url)?;
+        let token = match self.token.take() {
+            Some(token) => token,
+            None => match Token::resolve(None) {
+                // what is in a preferred cache is read without asking the server
+                Err(MapfmClientError::MissingToken) if self.cache.as_ref().is_some_and(|i| i.prefers_cache()) => Token::new(""),
+                res => res?,
+            },
+        };
+
+        let mut transport = HttpTransport::new(token, Some(&self.base_url));
+        if let Some(retry_policy) = self.retry_policy.take() {
+            transport = transport.with_retry_policy(retry_policy);
+        }
+        if let Some(config) = self.http_config.take() {
+            transport = transport.with_http_config(&config)?;
+        }
+        if let Some(cache) = self.cache.take() {
+            transport = transport.with_cache(cache);
+        }
+        if let Some(path) = self.recording.take() {
+            transport = transport.with_recording(path);
+        }
+
+        self.build_with_transport(transport)
+    }
+
+    /// A benchmarker which gets its problems from, and submits its solutions to,
+    /// `transport` instead of the server. The token, base url, retry policy, cache, http
+    /// config and recording are settings of the http transport, so they are not used.
+    pub fn build_with_transport<T: Transport>(self, transport: T) -> Result<MapfBenchmarker<T>, MapfmClientError> {
+        if self.benchmarks.is_empty() {
+            return Err(MapfmClientError::NoBenchmarks);
+        }
+
+        Ok(MapfBenchmarker::create(
+            transport,
+            self.benchmarks,
+            &self.algorithm_name,
+            &self.version,
+            self.debug,
+            self.solver,
+            self.options,
+        ))
+    }
+
+    /// A benchmarker for solving problems from disk with
+    /// [`run_local`](MapfBenchmarker::run_local), without a server. It needs no token or
+    /// benchmarks, and ignores everything only used when talking to the server.
+    pub fn build_local(self) -> MapfBenchmarker<Offline> {
+        MapfBenchmarker::create(
+            Offline,
+            self.benchmarks,
+            &self.algorithm_name,
+            &self.version,
+            self.debug,
+            self.solver,
+            self.options,
+        )
+    }
+}
+
+/// Fails unless `base_url` is an absolute http(s) url other paths can be joined onto.
+fn check_base_url(base_url: &str) -> Result<(), MapfmClientError> {
+    match Url::parse(base_url) {
+        Ok(url) if !url.cannot_be_a_base() && matches!(url.scheme(), "http" | "https") => Ok(()),
+        _ => Err(MapfmClientError::InvalidBaseUrl(base_url.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    pub fn test_builder() {
+        use crate::client::{BenchmarkDescriptor, MapfBenchmarker, MapfmClientError};
+        use crate::http_config::HttpConfig;
+        use crate::problem::Problem;
+        use crate::solution::Solution;
+
+        fn solver(_: Problem) -> Solution {
+            Solution { paths: vec![] }
+        }
+
+        let builder = || MapfBenchmarker::builder("test", solver).with_token("test-token");
+
+        assert!(matches!(builder().build(), Err(MapfmClientError::NoBenchmarks)));
+        for url in &["mapf.nl", "ftp://mapf.nl/", "mailto:someone@mapf.nl", ""] {
+            let res = builder().with_benchmark(BenchmarkDescriptor::from_identifier(1)).with_base_url(url).build();
+            assert!(matches!(res, Err(MapfmClientError::InvalidBaseUrl(_))), "{}", url);
+        }
+        let res = builder()
+            .with_benchmark(BenchmarkDescriptor::from_identifier(1))
+            .with_http_config(&HttpConfig::new().with_header("X Team", "a"))
+            .build();
+        assert!(matches!(res, Err(MapfmClientError::ClientConfig(_))));
+
+        let bm = builder()
+            .with_benchmarks(vec![BenchmarkDescriptor::from_identifier(1), BenchmarkDescriptor::from_identifier(2)])
+            .with_base_url("http://localhost:8080/")
+            .build()
+            .unwrap();
+        assert_eq!(bm.benchmark_descriptors().len(), 2);
+    }
+}
@@ -0,0 +1,97 @@
+use crate::client::{BenchmarkDescriptor, SerializedProblemList};
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+/// A directory of benchmark problem sets fetched before, so they don't
+/// have to be downloaded again (or can be used without a network at all).
+///
+/// Entries are keyed by the benchmark identifier and, for progressive
+/// benchmarks, the parameters of the [`ProgressiveDescriptor`](crate::ProgressiveDescriptor).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BenchmarkCache {
+    dir: PathBuf,
+    prefer_cache: bool,
+}
+
+impl BenchmarkCache {
+    /// A cache in `dir`, which is created when the first entry is stored.
+    ///
+    /// By default, problems are still fetched from the server (and the cache refreshed)
+    /// every time. Use [`prefer_cache`](Self::prefer_cache) to only go to the server for
+    /// benchmarks that aren't cached yet.
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+            prefer_cache: false,
+        }
+    }
+
+    pub fn prefer_cache(mut self, prefer_cache: bool) -> Self {
+        self.prefer_cache = prefer_cache;
+        self
+    }
+
+    pub(crate) fn prefers_cache(&self) -> bool {
+        self.prefer_cache
+    }
+
+    fn path(&self, descriptor: &BenchmarkDescriptor) -> PathBuf {
+        self.dir.join(format!("benchmark-{}.json", descriptor.name()))
+    }
+
+    /// The cached problems of a benchmark. An entry which can't be read is treated as missing.
+    pub(crate) fn load(&self, descriptor: &BenchmarkDescriptor) -> Option<SerializedProblemList> {
+        let data = fs::read(self.path(descriptor)).ok()?;
+        serde_json::from_slice(&data).ok()
+    }
+
+    pub(crate) fn store(&self, descriptor: &BenchmarkDescriptor, problems: &SerializedProblemList) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.path(descriptor), serde_json::to_vec(problems)?)
+    }
+
+    /// Removes the cached problems of a single benchmark, if there are any.
+    pub fn invalidate(&self, descriptor: &BenchmarkDescriptor) -> io::Result<()> {
+        match fs::remove_file(self.path(descriptor)) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            res => res,
+        }
+    }
+
+    /// Removes every cached benchmark.
+    pub fn clear(&self) -> io::Result<()> {
+        match fs::remove_dir_all(&self.dir) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            res => res,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cache::BenchmarkCache;
+    use crate::client::{BenchmarkDescriptor, ProgressiveDescriptor, SerializedProblemList};
+    use crate::testing::TempPath;
+
+    #[test]
+    pub fn test_cache() {
+        let dir = TempPath::new("cache");
+        let cache = BenchmarkCache::new(&dir);
+        let descriptor = BenchmarkDescriptor::from_identifier(3);
+        let progressive = BenchmarkDescriptor::new(3, Some(ProgressiveDescriptor::new(10, 1, 2, 0)));
+        let problems: SerializedProblemList = serde_json::from_str(r#"{"benchmarks": [
+            {"width": 2, "height": 1, "grid": [[0, 1]], "starts": [{"x": 0, "y": 0, "color": 0}], "goals": [{"x": 1, "y": 0, "color": 0}]}
+        ]}"#).unwrap();
+
+        assert!(cache.load(&descriptor).is_none());
+        cache.store(&descriptor, &problems).unwrap();
+        assert_eq!(cache.load(&descriptor).unwrap().problems(), problems.problems());
+        assert!(cache.load(&progressive).is_none());
+
+        cache.invalidate(&descriptor).unwrap();
+        assert!(cache.load(&descriptor).is_none());
+        cache.invalidate(&descriptor).unwrap();
+
+        cache.clear().unwrap();
+    }
+}
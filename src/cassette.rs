@@ -0,0 +1,137 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::{fs, io};
+
+/// The header the token is sent in, which is never written to a cassette.
+pub(crate) const TOKEN_HEADER: &str = "X-API-Token";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub(crate) struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub headers: BTreeMap<String, String>,
+    pub body: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub(crate) struct RecordedResponse {
+    pub status: u16,
+    pub headers: BTreeMap<String, String>,
+    pub body: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+struct Interaction {
+    request: RecordedRequest,
+    response: RecordedResponse,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct CassetteFile {
+    interactions: Vec<Interaction>,
+}
+
+/// Collects headers into a map, leaving out the token.
+pub(crate) fn headers<'a>(headers: impl IntoIterator<Item=(&'a str, &'a [u8])>) -> BTreeMap<String, String> {
+    headers.into_iter()
+        .filter(|(name, _)| !name.eq_ignore_ascii_case(TOKEN_HEADER))
+        .map(|(name, value)| (name.to_string(), String::from_utf8_lossy(value).into_owned()))
+        .collect()
+}
+
+/// Writes every request and its response to a cassette file. The file is rewritten
+/// after every interaction, so it is complete even when the process is stopped halfway.
+pub(crate) struct Recorder {
+    path: PathBuf,
+    cassette: Mutex<CassetteFile>,
+}
+
+impl Recorder {
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            cassette: Mutex::new(CassetteFile::default()),
+        }
+    }
+
+    pub fn record(&self, request: RecordedRequest, response: RecordedResponse) -> io::Result<()> {
+        let mut cassette = self.cassette.lock().unwrap();
+        cassette.interactions.push(Interaction { request, response });
+
+        fs::write(&self.path, serde_json::to_vec_pretty(&*cassette)?)
+    }
+}
+
+/// Answers requests with the responses from a cassette file, in the order they were recorded.
+pub(crate) struct Replayer {
+    interactions: Mutex<VecDeque<Interaction>>,
+}
+
+impl Replayer {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let cassette: CassetteFile = serde_json::from_slice(&fs::read(path)?)?;
+
+        Ok(Self {
+            interactions: Mutex::new(cassette.interactions.into()),
+        })
+    }
+
+    /// The recorded response to `request`. Fails when the session being replayed
+    /// deviates from the recorded one: the cassette has run out, or the next
+    /// recorded request is to a different endpoint. Bodies aren't compared, as
+    /// submissions contain how long solving took, which differs on every run.
+    pub fn replay(&self, request: &RecordedRequest) -> io::Result<RecordedResponse> {
+        let interaction = self.interactions.lock().unwrap()
+            .pop_front()
+            .ok_or_else(|| io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                format!("no recorded response left for {} {}", request.method, request.path)
+            ))?;
+
+        let recorded = &interaction.request;
+        if recorded.method != request.method || recorded.path != request.path {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("expected {} {} to be replayed, got {} {}", recorded.method, recorded.path, request.method, request.path)
+            ));
+        }
+
+        Ok(interaction.response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cassette::{headers, RecordedRequest, RecordedResponse, Recorder, Replayer};
+    use crate::testing::TempPath;
+    use std::fs;
+
+    #[test]
+    pub fn test_record_replay() {
+        let path = TempPath::new("cassette.json");
+
+        let request = |path: &str| RecordedRequest {
+            method: "POST".to_string(),
+            path: path.to_string(),
+            headers: headers(vec![("content-type", &b"application/json"[..]), ("x-api-token", &b"secret"[..])]),
+            body: "{}".to_string(),
+        };
+        let response = RecordedResponse {
+            status: 200,
+            headers: Default::default(),
+            body: r#"{"benchmarks": []}"#.to_string(),
+        };
+
+        let recorder = Recorder::new(&path);
+        recorder.record(request("/a"), response.clone()).unwrap();
+        recorder.record(request("/b"), response.clone()).unwrap();
+        assert!(!fs::read_to_string(&path).unwrap().contains("secret"));
+
+        let replayer = Replayer::load(&path).unwrap();
+        assert_eq!(replayer.replay(&request("/a")).unwrap(), response);
+        assert!(replayer.replay(&request("/a")).is_err());
+        assert!(replayer.replay(&request("/b")).is_err());
+    }
+}
@@ -0,0 +1,27 @@
+use crate::client::BenchmarkDescriptor;
+use serde::{Deserialize, Serialize};
+
+/// What the server knows about a benchmark, as listed by
+/// [`HttpTransport::list_benchmarks`](crate::HttpTransport::list_benchmarks).
+///
+/// **Experimental:** these fields are what the client expects the server to send, which
+/// hasn't been checked against a real response yet.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct BenchmarkInfo {
+    /// The identifier to build a [`BenchmarkDescriptor`] from.
+    pub id: usize,
+    /// The name of the map the problems of the benchmark are on.
+    pub map_name: String,
+    pub width: usize,
+    pub height: usize,
+    pub num_agents: usize,
+    pub num_teams: usize,
+    pub num_problems: usize,
+}
+
+impl BenchmarkInfo {
+    /// A descriptor for this benchmark, to run it (non-progressively).
+    pub fn descriptor(&self) -> BenchmarkDescriptor {
+        BenchmarkDescriptor::from_identifier(self.id)
+    }
+}
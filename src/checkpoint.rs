@@ -0,0 +1,240 @@
+use crate::client::{BenchmarkDescriptor, SerializedProblem};
+use crate::problem::Problem;
+use crate::runner::SolveResult;
+use crate::solver::SolverOutcome;
+use serde::{Serialize, Deserialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::{fs, io};
+
+#[derive(Serialize, Deserialize, Clone)]
+struct CheckpointedSolution {
+    index: usize,
+    outcome: SolverOutcome,
+    /// in nanoseconds
+    time: u64,
+}
+
+/// The first line of a state file: everything about an attempt which doesn't change
+/// while it runs. It is written once, when the attempt starts.
+#[derive(Serialize, Deserialize)]
+struct Header {
+    descriptor: BenchmarkDescriptor,
+    attempt_id: i64,
+    problems: Vec<SerializedProblem>,
+}
+
+/// Every line after the header records some progress, so keeping the state file up to
+/// date only takes appending a line, however many problems the attempt has.
+#[derive(Serialize, Deserialize)]
+enum Entry {
+    Solved(CheckpointedSolution),
+    Acknowledged(Vec<usize>),
+}
+
+struct Progress {
+    file: File,
+    solved: Vec<CheckpointedSolution>,
+    acknowledged: BTreeSet<usize>,
+}
+
+/// Keeps the state file of a running attempt up to date.
+pub(crate) struct Checkpointer {
+    path: PathBuf,
+    descriptor: BenchmarkDescriptor,
+    attempt_id: i64,
+    problems: Vec<Problem>,
+    progress: Mutex<Progress>,
+}
+
+fn line(value: &impl Serialize) -> io::Result<Vec<u8>> {
+    let mut line = serde_json::to_vec(value)?;
+    line.push(b'\n');
+    Ok(line)
+}
+
+impl Checkpointer {
+    /// Starts a new state file for an attempt, replacing any existing one at `path`.
+    /// The header is written to a temporary file first, so being stopped halfway
+    /// through never leaves a corrupt state file behind.
+    pub fn create(path: &Path, descriptor: &BenchmarkDescriptor, attempt_id: i64, problems: &[Problem]) -> io::Result<Self> {
+        let header = Header {
+            descriptor: descriptor.clone(),
+            attempt_id,
+            problems: problems.iter().map(SerializedProblem::from_problem).collect(),
+        };
+
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, line(&header)?)?;
+        fs::rename(tmp, path)?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            descriptor: descriptor.clone(),
+            attempt_id,
+            problems: problems.to_vec(),
+            progress: Mutex::new(Progress {
+                file: OpenOptions::new().append(true).open(path)?,
+                solved: vec![],
+                acknowledged: BTreeSet::new(),
+            }),
+        })
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let contents = fs::read(path)?;
+        let mut lines = contents.split_inclusive(|&i| i == b'\n');
+        let first = lines.next().unwrap_or_default();
+        let header: Header = serde_json::from_slice(first)?;
+
+        let mut solved = Vec::new();
+        let mut acknowledged = BTreeSet::new();
+        let mut valid = first.len();
+        for entry in lines {
+            // a line which wasn't written completely is where the process was stopped
+            match serde_json::from_slice(entry) {
+                Ok(Entry::Solved(solution)) => solved.push(solution),
+                Ok(Entry::Acknowledged(indices)) => acknowledged.extend(indices),
+                Err(_) if !entry.ends_with(b"\n") => break,
+                Err(e) => return Err(e.into()),
+            }
+            valid += entry.len();
+        }
+
+        let file = OpenOptions::new().append(true).open(path)?;
+        // so the next line isn't appended to an incomplete one
+        file.set_len(valid as u64)?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            descriptor: header.descriptor,
+            attempt_id: header.attempt_id,
+            problems: header.problems.iter().map(|i| i.to_problem()).collect(),
+            progress: Mutex::new(Progress {
+                file,
+                solved,
+                acknowledged,
+            }),
+        })
+    }
+
+    /// Failing to update the state file isn't fatal: it only means less work can be
+    /// recovered when resuming.
+    fn append(progress: &mut Progress, entry: &Entry) {
+        if let Ok(line) = line(entry) {
+            let _ = progress.file.write_all(&line);
+        }
+    }
+
+    /// Records the result for the problem at `index`.
+    pub fn record(&self, index: usize, (outcome, _, time): &SolveResult) {
+        let solution = CheckpointedSolution {
+            index,
+            outcome: outcome.clone(),
+            time: time.as_nanos() as u64,
+        };
+
+        let mut progress = self.progress.lock().unwrap();
+        Self::append(&mut progress, &Entry::Solved(solution.clone()));
+        progress.solved.push(solution);
+    }
+
+    pub fn acknowledge(&self, indices: impl IntoIterator<Item=usize>) {
+        let indices: Vec<_> = indices.into_iter().collect();
+
+        let mut progress = self.progress.lock().unwrap();
+        Self::append(&mut progress, &Entry::Acknowledged(indices.clone()));
+        progress.acknowledged.extend(indices);
+    }
+
+    pub fn descriptor(&self) -> BenchmarkDescriptor {
+        self.descriptor.clone()
+    }
+
+    pub fn attempt_id(&self) -> i64 {
+        self.attempt_id
+    }
+
+    pub fn acknowledged(&self) -> BTreeSet<usize> {
+        self.progress.lock().unwrap().acknowledged.clone()
+    }
+
+    /// The problems which don't have a result yet, with their index in the attempt.
+    pub fn remaining(&self) -> Vec<(usize, Problem)> {
+        let progress = self.progress.lock().unwrap();
+        let solved: BTreeSet<_> = progress.solved.iter().map(|i| i.index).collect();
+
+        self.problems.iter()
+            .cloned()
+            .enumerate()
+            .filter(|(index, _)| !solved.contains(index))
+            .collect()
+    }
+
+    /// All results recorded so far, ordered by index.
+    pub fn results(&self) -> Vec<(usize, SolveResult)> {
+        let progress = self.progress.lock().unwrap();
+
+        let mut res: Vec<_> = progress.solved.iter()
+            .map(|i| (i.index, (
+                i.outcome.clone(),
+                self.problems[i.index].clone(),
+                Duration::from_nanos(i.time),
+            )))
+            .collect();
+        res.sort_by_key(|(index, _)| *index);
+        res.dedup_by_key(|(index, _)| *index);
+        res
+    }
+
+    /// Removes the state file, once the attempt has been submitted completely.
+    pub fn remove(&self) -> io::Result<()> {
+        fs::remove_file(&self.path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::checkpoint::Checkpointer;
+    use crate::client::BenchmarkDescriptor;
+    use crate::solution::Solution;
+    use crate::solver::SolverOutcome;
+    use crate::coordinate::Coordinate;
+    use crate::testing::{problem, TempPath};
+    use std::fs;
+    use std::io::Write;
+    use std::time::Duration;
+
+    #[test]
+    pub fn test_resume_checkpoint() {
+        let path = TempPath::new("checkpoint.json");
+        let problems: Vec<_> = (1..=3).map(problem).collect();
+        let solution = Solution { paths: vec![vec![Coordinate::new(0, 0)]] };
+
+        let checkpoint = Checkpointer::create(&path, &BenchmarkDescriptor::from_identifier(5), 42, &problems).unwrap();
+        checkpoint.record(1, &(SolverOutcome::Solved(solution.clone()), problems[1].clone(), Duration::from_millis(3)));
+        checkpoint.acknowledge(vec![1]);
+        // the problems are only written once, with every update taking a line of its own
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 3);
+
+        // as if the process was stopped while writing
+        fs::OpenOptions::new().append(true).open(&path).unwrap().write_all(br#"{"Solved":{"ind"#).unwrap();
+
+        let checkpoint = Checkpointer::load(&path).unwrap();
+        assert_eq!(checkpoint.attempt_id(), 42);
+        assert_eq!(checkpoint.descriptor(), BenchmarkDescriptor::from_identifier(5));
+        assert_eq!(checkpoint.remaining(), vec![(0, problems[0].clone()), (2, problems[2].clone())]);
+        assert_eq!(checkpoint.results(), vec![(1, (SolverOutcome::Solved(solution), problems[1].clone(), Duration::from_millis(3)))]);
+        assert_eq!(checkpoint.acknowledged().into_iter().collect::<Vec<_>>(), vec![1]);
+
+        checkpoint.record(2, &(SolverOutcome::TimedOut, problems[2].clone(), Duration::from_millis(4)));
+        assert_eq!(Checkpointer::load(&path).unwrap().remaining(), vec![(0, problems[0].clone())]);
+
+        checkpoint.remove().unwrap();
+        assert!(!path.exists());
+    }
+}
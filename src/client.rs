@@ -1,31 +1,122 @@
 use std::error::Error;
-use crate::solution::Solution;
 use crate::problem::Problem;
-use reqwest::blocking::Client;
-use std::time::Duration;
+use std::sync::{mpsc, Mutex};
+use std::collections::{BTreeSet, HashMap};
 use crate::marked::MarkedCoordinate;
-use crate::coordinate::Coordinate;
 use crate::grid::Grid;
-use reqwest::Url;
 use thiserror::Error;
 use serde::{Serialize, Deserialize};
-#[cfg(feature = "cbindgen-on")]
-use crate::ffi::CallBack;
+use crate::retry::NOT_HANDLED;
+use crate::runner::{run_problems, Escalation, SolveResult, TimeLimits};
+use crate::solver::{SharedSolver, Solver};
+use crate::checkpoint::Checkpointer;
+use crate::artifacts::Artifacts;
+use crate::local::load_problems;
+use crate::transport::{BenchmarkRequest, Transport};
+use crate::http::{batch_submission_body, submission_body, HttpTransport};
+use crate::builder::{MapfBenchmarkerBuilder, RunOptions};
+use crate::report::{BenchmarkOutcome, RunReport};
+use crate::observer::{notify, problem_event, Event, Observers};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 #[derive(Debug, Error)]
 #[cfg_attr(feature = "cbindgen-on", repr(C))]
 pub enum MapfmClientError {
     #[error("failed to parse url")]
-    UrlParse(Box<dyn Error>),
+    UrlParse(Box<dyn Error + Send + Sync>),
 
     #[error("request error {0}")]
     RequestError(reqwest::Error),
 
     #[error("json decode error {0}")]
-    JsonDecodeError(reqwest::Error),
+    JsonDecodeError(serde_json::Error),
 
-    #[error("status code: {0}")]
-    Status(u16)
+    #[error("failed to read or write checkpoint {0}")]
+    Checkpoint(std::io::Error),
+
+    #[error("failed to read local problems {0}")]
+    LocalProblems(std::io::Error),
+
+    #[error("failed to record or replay cassette {0}")]
+    Cassette(std::io::Error),
+
+    #[error("failed to write artifacts {0}")]
+    Artifacts(std::io::Error),
+
+    #[error("failed to generate problems: {0}")]
+    Generator(String),
+
+    #[error("authentication failed (is the token correct?): {0}")]
+    Unauthorized(String),
+
+    #[error("benchmark not found: {0}")]
+    BenchmarkNotFound(String),
+
+    #[error("not found: {0}")]
+    NotFound(String),
+
+    #[error("request rejected by server: {0}")]
+    Rejected(String),
+
+    #[error("rate limited by server: {0}")]
+    RateLimited(String),
+
+    #[error("status code: {0}: {1}")]
+    Status(u16, String),
+
+    #[error("no token given (pass one, set {} or put it in the config file)", crate::token::TOKEN_ENV)]
+    MissingToken,
+
+    #[error("failed to read token file {0}")]
+    TokenFile(std::io::Error),
+
+    #[error("invalid http client configuration: {0}")]
+    ClientConfig(Box<dyn Error + Send + Sync>),
+
+    #[error("invalid base url {0:?} (expected an absolute http or https url)")]
+    InvalidBaseUrl(String),
+
+    #[error("no benchmarks to run")]
+    NoBenchmarks,
+}
+
+impl MapfmClientError {
+    /// Builds the error for a response with a non-200 status code, taking the
+    /// message out of the body when the server sent a json error object. What wasn't
+    /// found depends on the endpoint, so a 404 is a [`NotFound`](Self::NotFound).
+    pub(crate) fn from_status(status: u16, body: String) -> Self {
+        let message = serde_json::from_str::<ServerErrorMessage>(&body)
+            .ok()
+            .and_then(|i| i.message.or(i.error).or(i.detail))
+            .unwrap_or(body);
+
+        match status {
+            401 | 403 => Self::Unauthorized(message),
+            404 => Self::NotFound(message),
+            400 | 409 | 422 => Self::Rejected(message),
+            429 => Self::RateLimited(message),
+            _ => Self::Status(status, message),
+        }
+    }
+
+    /// Whether the request which failed with this error surely wasn't handled by the
+    /// server, so sending it again can't make the server handle it twice.
+    pub(crate) fn unhandled(&self) -> bool {
+        match self {
+            Self::RequestError(e) => e.is_connect(),
+            Self::RateLimited(_) => true,
+            Self::Status(status, _) => NOT_HANDLED.contains(status),
+            _ => false,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ServerErrorMessage {
+    message: Option<String>,
+    error: Option<String>,
+    detail: Option<String>,
 }
 
 
@@ -52,9 +143,25 @@ impl ProgressiveDescriptor {
             max_diff
         }
     }
+
+    pub fn max_agents(&self) -> usize {
+        self.max_agents
+    }
+
+    pub fn min_agents(&self) -> usize {
+        self.min_agents
+    }
+
+    pub fn num_teams(&self) -> usize {
+        self.num_teams
+    }
+
+    pub fn max_diff(&self) -> usize {
+        self.max_diff
+    }
 }
 
-#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug, Hash)]
 #[cfg_attr(feature = "cbindgen-on", repr(C))]
 pub struct BenchmarkDescriptor {
     identifier: usize,
@@ -65,6 +172,17 @@ impl BenchmarkDescriptor {
     pub(crate) fn progressive(&self) -> bool {
         self.progressive_descriptor.is_some()
     }
+
+    /// Uniquely names this descriptor, for use in file names.
+    pub(crate) fn name(&self) -> String {
+        match &self.progressive_descriptor {
+            Some(i) => format!(
+                "{}-progressive-{}-{}-{}-{}",
+                self.identifier, i.min_agents, i.max_agents, i.num_teams, i.max_diff
+            ),
+            None => self.identifier.to_string(),
+        }
+    }
 }
 
 impl BenchmarkDescriptor {
@@ -78,252 +196,618 @@ impl BenchmarkDescriptor {
     pub fn from_identifier(identifier: usize) -> Self {
         Self::new(identifier, None)
     }
+
+    pub fn identifier(&self) -> usize {
+        self.identifier
+    }
+
+    pub fn progressive_descriptor(&self) -> Option<&ProgressiveDescriptor> {
+        self.progressive_descriptor.as_deref()
+    }
+}
+
+/// What a run over several benchmarks does when one of them fails.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum ErrorPolicy {
+    /// Stop at the first benchmark which fails.
+    #[default]
+    FailFast,
+    /// Run all benchmarks, whether the ones before them failed or not.
+    Continue,
 }
 
 #[cfg_attr(feature = "cbindgen-on", repr(C))]
-pub struct MapfBenchmarker {
-    token: String,
+pub struct MapfBenchmarker<T = HttpTransport> {
+    transport: T,
     benchmark_descriptors: Vec<BenchmarkDescriptor>,
     algorithm_name: String,
     version: String,
     debug: bool,
+    token_validation: bool,
 
-    #[cfg(feature = "cbindgen-on")]
-    solver: CallBack,
-    #[cfg(not(feature = "cbindgen-on"))]
-    solver: fn(Problem) -> Solution,
+    solver: SharedSolver,
 
-    base_url: String,
+    time_limits: TimeLimits,
+    failure_cutoff: Option<usize>,
+    error_policy: ErrorPolicy,
+    workers: usize,
+    stream_batch_size: Option<usize>,
+    checkpoint_path: Option<PathBuf>,
+    artifacts_path: Option<PathBuf>,
+    observers: Observers,
 
-    client: Client
+    /// For every attempt, the indices of the problems the server has accepted solutions for.
+    acknowledged: Mutex<HashMap<i64, BTreeSet<usize>>>,
 }
 
 impl MapfBenchmarker {
-    #[cfg(feature = "cbindgen-on")]
-    pub fn new(
-        token: &str,
+    /// Starts setting up a benchmarker running `solver`, under the name `algorithm_name`.
+    pub fn builder(algorithm_name: &str, solver: impl Solver + 'static) -> MapfBenchmarkerBuilder {
+        MapfBenchmarkerBuilder::new(algorithm_name, Arc::new(solver))
+    }
+}
+
+impl<T> MapfBenchmarker<T> {
+    pub(crate) fn create(
+        transport: T,
         benchmark: Vec<BenchmarkDescriptor>,
         algorithm_name: &str,
         version: &str,
         debug: bool,
-        solver: CallBack,
-        base_url: Option<&str>,
+        solver: SharedSolver,
+        options: RunOptions,
     ) -> Self {
         Self {
-            token: token.to_string(),
+            transport,
             benchmark_descriptors: benchmark,
             algorithm_name: algorithm_name.to_string(),
             version: version.to_string(),
             debug,
+            token_validation: options.token_validation,
             solver,
-            base_url: base_url.map(|i| i.to_string()).unwrap_or("https://mapf.nl/".to_string()),
+            time_limits: options.time_limits,
+            failure_cutoff: options.failure_cutoff,
+            error_policy: options.error_policy,
+            workers: options.workers,
+            stream_batch_size: options.stream_batch_size,
+            checkpoint_path: options.checkpoint_path,
+            artifacts_path: options.artifacts_path,
+            observers: options.observers,
+
+            acknowledged: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    pub fn benchmark_descriptors(&self) -> &[BenchmarkDescriptor] {
+        &self.benchmark_descriptors
+    }
+
+    /// The indices of the problems in an attempt for which the server has
+    /// accepted a solution.
+    pub fn acknowledged(&self, attempt_id: i64) -> Vec<usize> {
+        self.acknowledged.lock().unwrap()
+            .get(&attempt_id)
+            .map(|i| i.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    fn acknowledge(&self, attempt_id: i64, indices: impl IntoIterator<Item=usize>) {
+        self.acknowledged.lock().unwrap()
+            .entry(attempt_id)
+            .or_default()
+            .extend(indices);
+    }
 
-            client: Client::new()
+    fn notify(&self, event: Event) {
+        notify(&self.observers, event);
+    }
+
+    /// Writes the artifact of a submission of the solutions for `indices`, and lets the
+    /// observers know it is being sent.
+    fn sending(&self, descriptor: &BenchmarkDescriptor, attempt_id: i64, indices: &[usize], body: impl FnOnce() -> Vec<u8>) {
+        if let Some(artifacts) = self.artifacts(descriptor, Some(attempt_id)) {
+            artifacts.write_submission(&body());
         }
+        self.notify(Event::SubmissionSent { attempt_id, indices });
     }
 
-    #[cfg(not(feature = "cbindgen-on"))]
-    pub fn new(
-        token: &str,
-        benchmark: Vec<BenchmarkDescriptor>,
-        algorithm_name: &str,
-        version: &str,
-        debug: bool,
-        solver: fn(Problem) -> Solution,
-        base_url: Option<&str>,
-    ) -> Self {
-        Self {
-            token: token.to_string(),
-            benchmark_descriptors: benchmark,
-            algorithm_name: algorithm_name.to_string(),
-            version: version.to_string(),
-            debug,
-            solver,
-            base_url: base_url.map(|i| i.to_string()).unwrap_or("https://mapf.nl/".to_string()),
+    /// Lets the observers know how submitting the solutions for `indices` went, and
+    /// remembers them (in the `checkpoint` too) when the server accepted them.
+    fn submitted(&self, attempt_id: i64, indices: &[usize], checkpoint: Option<&Checkpointer>, res: Result<(), MapfmClientError>) -> Result<(), MapfmClientError> {
+        match &res {
+            Ok(()) => {
+                self.acknowledge(attempt_id, indices.iter().copied());
+                if let Some(checkpoint) = checkpoint {
+                    checkpoint.acknowledge(indices.iter().copied());
+                }
+                self.notify(Event::SubmissionAcknowledged { attempt_id, indices });
+            }
+            Err(error) => self.notify(Event::SubmissionFailed { attempt_id, indices, error }),
+        }
+
+        res
+    }
+
+    /// The state file kept for attempts at `descriptor`, when the benchmarker was built
+    /// [`with_checkpoint`](MapfBenchmarkerBuilder::with_checkpoint). Benchmarks have a file
+    /// of their own, so one which failed to submit can still be resumed after the
+    /// next one has run.
+    pub fn checkpoint_file(&self, descriptor: &BenchmarkDescriptor) -> Option<PathBuf> {
+        self.checkpoint_path.as_ref().map(|dir| dir.join(format!("{}.json", descriptor.name())))
+    }
+
+    fn create_checkpoint(&self, descriptor: &BenchmarkDescriptor, id: i64, problems: &[Problem]) -> Result<Option<Arc<Checkpointer>>, MapfmClientError> {
+        self.checkpoint_file(descriptor)
+            .map(|path| {
+                std::fs::create_dir_all(path.parent().unwrap_or(&path))?;
+                Checkpointer::create(&path, descriptor, id, problems).map(Arc::new)
+            })
+            .transpose()
+            .map_err(MapfmClientError::Checkpoint)
+    }
+
+    fn artifacts(&self, descriptor: &BenchmarkDescriptor, attempt_id: Option<i64>) -> Option<Artifacts> {
+        self.artifacts_path.as_ref().map(|path| Artifacts::new(path, descriptor, attempt_id))
+    }
 
-            client: Client::new()
+    /// The artifacts of a new attempt (or run without an attempt), with its problems written.
+    fn create_artifacts(&self, descriptor: &BenchmarkDescriptor, attempt_id: Option<i64>, problems: &[Problem]) -> Result<Option<Artifacts>, MapfmClientError> {
+        let artifacts = self.artifacts(descriptor, attempt_id);
+        if let Some(artifacts) = &artifacts {
+            artifacts.write_problems(problems).map_err(MapfmClientError::Artifacts)?;
         }
+
+        Ok(artifacts)
     }
 
+    fn escalation(&self) -> Option<Escalation> {
+        self.failure_cutoff.map(Escalation::new)
+    }
 
-    fn get_benchmark_data(&self, descriptor: &BenchmarkDescriptor, attempt: bool) -> GetBenchmarkData {
-        GetBenchmarkData {
-            algorithm: self.algorithm_name.clone(),
-            version: self.version.clone(),
+    fn request<'a>(&'a self, descriptor: &'a BenchmarkDescriptor) -> BenchmarkRequest<'a> {
+        BenchmarkRequest {
+            descriptor,
+            algorithm_name: &self.algorithm_name,
+            version: &self.version,
             debug: self.debug,
-            progressive: descriptor.progressive(),
-            progressive_description: descriptor.progressive_descriptor.clone(),
-            create_attempt: attempt,
         }
     }
 
-    fn get_benchmark(&self, descriptor: &BenchmarkDescriptor) -> Result<Vec<Problem>, MapfmClientError> {
-        let url = Url::parse(&self.base_url)
-            .map_err(|i| MapfmClientError::UrlParse(Box::new(i)))?
-            .join(&format!("/api/benchmark/attempt/{}", descriptor.identifier))
-            .map_err(|i| MapfmClientError::UrlParse(Box::new(i)))?;
+    /// What solving the problems of a run takes, keeping the results in `checkpoint`
+    /// and `artifacts`.
+    fn solving(&self, checkpoint: Option<Arc<Checkpointer>>, artifacts: Option<Artifacts>) -> Solving {
+        Solving {
+            solver: self.solver.clone(),
+            limits: self.time_limits,
+            escalation: self.escalation(),
+            workers: self.workers,
+            observers: self.observers.clone(),
+            checkpoint,
+            artifacts,
+            indices: None,
+        }
+    }
+
+    /// Solves the problems in a local directory instead of ones fetched from the server.
+    /// Every `.json` file in `dir` is read as one problem, in the same format the server
+    /// uses. Nothing is sent to the server, so the transport and benchmark descriptors of
+    /// this benchmarker aren't used.
+    ///
+    /// Returns a report of the run, like [`run`](MapfBenchmarker::run) does for every
+    /// benchmark, but without a descriptor or attempt. Problems are reported in file name
+    /// order.
+    pub fn run_local(&self, dir: impl AsRef<Path>) -> Result<RunReport, MapfmClientError> {
+        let problems = load_problems(dir.as_ref()).map_err(MapfmClientError::LocalProblems)?;
+        let results = self.solving(None, None).run(problems, |_, _| {});
+
+        Ok(RunReport::new(None, None, &results))
+    }
+}
+
+/// Everything solving the problems of a run takes, owned, so it can be moved onto
+/// tokio's blocking thread pool as well as be used in place.
+struct Solving {
+    solver: SharedSolver,
+    limits: TimeLimits,
+    escalation: Option<Escalation>,
+    workers: usize,
+    observers: Observers,
+    checkpoint: Option<Arc<Checkpointer>>,
+    artifacts: Option<Artifacts>,
+    /// The index in the attempt of every problem solved, when only some of them are.
+    indices: Option<Vec<usize>>,
+}
+
+impl Solving {
+    /// Solves `problems`, letting the observers know about every one of them and keeping
+    /// its result in the checkpoint and artifacts. `on_result` then gets every result,
+    /// with the index of its problem in the attempt.
+    fn run(self, problems: Vec<Problem>, on_result: impl Fn(usize, &SolveResult) + Sync) -> Vec<SolveResult> {
+        let Solving { solver, limits, escalation, workers, observers, checkpoint, artifacts, indices } = self;
+        let attempt_index = |index: usize| indices.as_ref().map_or(index, |i| i[index]);
+
+        run_problems(&solver, problems, limits, escalation, workers, &|index| {
+            notify(&observers, Event::ProblemStarted { index: attempt_index(index) });
+        }, &|index, res| {
+            let index = attempt_index(index);
+            notify(&observers, problem_event(index, res));
+            if let Some(checkpoint) = &checkpoint {
+                checkpoint.record(index, res);
+            }
+            if let Some(artifacts) = &artifacts {
+                artifacts.write_result(index, res);
+            }
+
+            on_result(index, res);
+        })
+    }
+}
+
+/// The streamed results which haven't been submitted yet.
+struct Pending {
+    batch_size: usize,
+    results: Vec<(usize, SolveResult)>,
+}
+
+impl Pending {
+    fn new(batch_size: usize) -> Self {
+        Self {
+            batch_size,
+            results: Vec::new(),
+        }
+    }
+
+    /// Adds a result, returning whether there is a full batch to submit.
+    fn push(&mut self, res: (usize, SolveResult)) -> bool {
+        self.results.push(res);
+        self.results.len() >= self.batch_size
+    }
 
-        let req = self.client
-            .post(url)
-            .header("X-API-Token", &self.token)
-            .json(&self.get_benchmark_data(descriptor, false));
+    /// Takes in how submitting the pending results went. A batch the server didn't get
+    /// is kept, to be sent again with the next one; any other error ends the stream.
+    /// The remaining problems are still solved, and end up in the checkpoint.
+    fn submitted(&mut self, res: Result<(), MapfmClientError>) -> Result<(), MapfmClientError> {
+        match res {
+            Ok(()) => {
+                self.results.clear();
+                Ok(())
+            }
+            Err(e) if e.unhandled() => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+}
 
-        let resp = req.send().map_err(MapfmClientError::RequestError)?;
+impl<T: Transport> MapfBenchmarker<T> {
+    fn submit_solutions(&self, descriptor: &BenchmarkDescriptor, solutions: &[SolveResult], id: i64) -> Result<(), MapfmClientError> {
+        let indices: Vec<_> = (0..solutions.len()).collect();
+        self.sending(descriptor, id, &indices, || submission_body(descriptor, solutions));
 
+        self.submitted(id, &indices, None, self.transport.submit_solutions(descriptor, id, solutions))
+    }
 
-        let data: SerializedProblemList = resp.json().map_err(MapfmClientError::JsonDecodeError)?;
+    fn submit_batch(&self, descriptor: &BenchmarkDescriptor, batch: &[(usize, SolveResult)], id: i64, checkpoint: Option<&Checkpointer>) -> Result<(), MapfmClientError> {
+        let indices: Vec<_> = batch.iter().map(|(index, _)| *index).collect();
+        self.sending(descriptor, id, &indices, || batch_submission_body(descriptor, batch));
 
-        Ok(data.benchmarks.iter().map(|i| i.to_problem()).collect())
+        self.submitted(id, &indices, checkpoint, self.transport.submit_batch(descriptor, id, batch))
     }
 
-    fn start_attempt(&self, descriptor: &BenchmarkDescriptor) -> Result<(Vec<Problem>, i64), MapfmClientError> {
-        let url = Url::parse(&self.base_url)
-            .map_err(|i| MapfmClientError::UrlParse(Box::new(i)))?
-            .join(&format!("/api/benchmark/attempt/{}", descriptor.identifier))
-            .map_err(|i| MapfmClientError::UrlParse(Box::new(i)))?;
+    fn run_benchmark_streaming(&self, descriptor: &BenchmarkDescriptor, problems: Vec<Problem>, id: i64, batch_size: usize, checkpoint: Option<Arc<Checkpointer>>, artifacts: Option<Artifacts>) -> Result<Vec<SolveResult>, MapfmClientError> {
+        let (tx, rx) = mpsc::channel();
+        let solving = self.solving(checkpoint.clone(), artifacts);
+
+        std::thread::scope(|s| {
+            // submitting happens on a thread of its own, so solving never waits for the server
+            let submitter = s.spawn(move || {
+                let mut pending = Pending::new(batch_size);
+                for res in rx {
+                    if pending.push(res) {
+                        pending.submitted(self.submit_batch(descriptor, &pending.results, id, checkpoint.as_deref()))?;
+                    }
+                }
+
+                if !pending.results.is_empty() {
+                    self.submit_batch(descriptor, &pending.results, id, checkpoint.as_deref())?;
+                }
+                Ok(())
+            });
+
+            let tx = Mutex::new(tx);
+            let results = solving.run(problems, |index, res| {
+                let _ = tx.lock().unwrap().send((index, res.clone()));
+            });
+            // lets the submitter know there is nothing more to come
+            drop(tx);
+
+            submitter.join().unwrap_or_else(|e| std::panic::resume_unwind(e))?;
+            Ok(results)
+        })
+    }
 
-        let req = self.client
-            .post(url)
-            .header("X-API-Token", &self.token)
-            .json(&self.get_benchmark_data(descriptor, true));
+    fn run_attempt(&self, descriptor: &BenchmarkDescriptor, problems: Vec<Problem>, id: i64) -> Result<Vec<SolveResult>, MapfmClientError> {
+        let checkpoint = self.create_checkpoint(descriptor, id, &problems)?;
+        let artifacts = self.create_artifacts(descriptor, Some(id), &problems)?;
 
-        let resp = req.send().map_err(MapfmClientError::RequestError)?;
+        let results = if let Some(batch_size) = self.stream_batch_size {
+            self.run_benchmark_streaming(descriptor, problems, id, batch_size, checkpoint.clone(), artifacts)?
+        } else {
+            let results = self.solving(checkpoint.clone(), artifacts).run(problems, |_, _| {});
+            self.submit_solutions(descriptor, &results, id)?;
+            results
+        };
 
-        let status = resp.status().as_u16();
-        if status != 200 {
-            return Err(MapfmClientError::Status(status))
+        if let Some(checkpoint) = checkpoint {
+            checkpoint.remove().map_err(MapfmClientError::Checkpoint)?;
         }
 
-        let data: SerializedProblemList = resp.json().map_err(MapfmClientError::JsonDecodeError)?;
+        Ok(results)
+    }
 
-        Ok((
-            data.benchmarks.iter().map(|i| i.to_problem()).collect(),
-            data.attempt_id
-        ))
+    /// Checks the token is accepted, by requesting the problems of the first benchmark
+    /// without starting an attempt, so a wrong token is found out before any solving is
+    /// done. As the server has no lighter request for this, it downloads all of those
+    /// problems. Benchmarkers built [`with_token_validation`](MapfBenchmarkerBuilder::with_token_validation)
+    /// do this at the start of every run, which is off by default for that reason.
+    pub fn validate_token(&self) -> Result<(), MapfmClientError> {
+        let descriptor = self.benchmark_descriptors.first().ok_or(MapfmClientError::NoBenchmarks)?;
+
+        self.transport.validate(&self.request(descriptor))
     }
 
-    fn run_benchmark(&self, problems: Vec<Problem>) -> Vec<(Solution, Problem, Duration)> {
+    /// Finishes an attempt from the state file written by a benchmarker configured
+    /// [`with_checkpoint`](MapfBenchmarkerBuilder::with_checkpoint): solves only the problems which
+    /// weren't solved yet, and submits them to the original attempt together
+    /// with the solutions from the file.
+    pub fn resume(&self, path: impl AsRef<Path>) -> Result<RunReport, MapfmClientError> {
+        let checkpoint = Arc::new(Checkpointer::load(path.as_ref()).map_err(MapfmClientError::Checkpoint)?);
+        let descriptor = checkpoint.descriptor();
+        let id = checkpoint.attempt_id();
+        if self.token_validation {
+            self.transport.validate(&self.request(&descriptor))?;
+        }
 
-        problems.into_iter().map(|i|  {
-            let t1 = std::time::Instant::now();
+        let (indices, problems): (Vec<_>, Vec<_>) = checkpoint.remaining().into_iter().unzip();
+        let mut solving = self.solving(Some(checkpoint.clone()), self.artifacts(&descriptor, Some(id)));
+        // the solver keeps skipping what it was skipping before it was stopped
+        if let Some(escalation) = &solving.escalation {
+            for (_, res) in checkpoint.results() {
+                escalation.record(&res);
+            }
+        }
+        solving.indices = Some(indices);
+
+        self.notify(Event::AttemptStarted { descriptor: &descriptor, attempt_id: Some(id), num_problems: problems.len() });
+        solving.run(problems, |_, _| {});
+
+        // when solutions were streamed before, only send the ones the server doesn't have yet
+        let acknowledged = checkpoint.acknowledged();
+        let results = checkpoint.results();
+        let solutions: Vec<_> = results.iter().map(|(_, res)| res.clone()).collect();
+        if acknowledged.is_empty() {
+            self.submit_solutions(&descriptor, &solutions, id)?;
+        } else {
+            let remaining: Vec<_> = results.into_iter()
+                .filter(|(index, _)| !acknowledged.contains(index))
+                .collect();
+            if !remaining.is_empty() {
+                self.submit_batch(&descriptor, &remaining, id, Some(&checkpoint))?;
+            }
+        }
 
-            #[cfg(feature = "cbindgen-on")]
-            let solution = self.solver.call(i.clone());
-            #[cfg(not(feature = "cbindgen-on"))]
-            let solution = (self.solver)(i.clone());
+        checkpoint.remove().map_err(MapfmClientError::Checkpoint)?;
 
-            let duration = std::time::Instant::now().duration_since(t1);
-            (solution, i, duration)
-        }).collect()
+        let report = RunReport::new(Some(&descriptor), Some(id), &solutions);
+        self.notify(Event::BenchmarkFinished { report: &report });
+        Ok(report)
     }
 
-    fn submit_solutions(&self, descriptor: &BenchmarkDescriptor, solutions: Vec<(Solution, Problem, Duration)>, id: i64) -> Result<(), MapfmClientError> {
-        let url = Url::parse(&self.base_url)
-            .map_err(|i| MapfmClientError::UrlParse(Box::new(i)))?
-            .join(&format!("/api/solutions/submit/{}", id))
-            .map_err(|i| MapfmClientError::UrlParse(Box::new(i)))?;
+    fn run_descriptor(&self, descriptor: &BenchmarkDescriptor, make_attempt: bool) -> Result<RunReport, MapfmClientError> {
+        let report = if make_attempt {
+            let (problems, id) = self.transport.start_attempt(&self.request(descriptor))?;
+            self.notify(Event::AttemptStarted { descriptor, attempt_id: Some(id), num_problems: problems.len() });
 
-        let req = self.client
-            .post(url)
-            .header("X-API-Token", &self.token)
-            .json(&SubmitSolutionData {
-                solutions: solutions.into_iter().map(|(sol, prob, time)| {
-                    SerializedSolution {
-                        time: time.as_nanos(),
-                        solution: SerializedSolutionData {
-                            paths: sol.paths.into_iter()
-                                .map(|i| SerializedPath {
-                                    route: i
-                                })
-                                .collect()
-                        },
-                        progressive_params: descriptor.progressive_descriptor.clone().map(|i| {
-                            ProgressiveParams {
-                                num_agents: prob.starts.len(),
-                                num_teams: i.num_teams,
-                                max_diff: i.max_diff,
-                                starts: prob.starts,
-                                goals: prob.goals
-                            }
-                        })
-                    }
-                }).collect(),
-                benchmark: descriptor.identifier,
-                progressive: descriptor.progressive()
-            });
+            let results = self.run_attempt(descriptor, problems, id)?;
+            RunReport::new(Some(descriptor), Some(id), &results)
+        } else {
+            let problems = self.transport.get_benchmark(&self.request(descriptor))?;
+            self.notify(Event::AttemptStarted { descriptor, attempt_id: None, num_problems: problems.len() });
+
+            let artifacts = self.create_artifacts(descriptor, None, &problems)?;
+            let results = self.solving(None, artifacts).run(problems, |_, _| {});
+            RunReport::new(Some(descriptor), None, &results)
+        };
 
+        self.notify(Event::BenchmarkFinished { report: &report });
+        Ok(report)
+    }
 
-        let resp = req.send().map_err(MapfmClientError::RequestError)?;
-        let status = resp.status().as_u16();
-        if status != 200 {
-            return Err(MapfmClientError::Status(status))
+    /// Adds the outcome of running `descriptor` to `outcomes`, returning whether
+    /// to go on with the next benchmark.
+    fn record_outcome(&self, outcomes: &mut Vec<BenchmarkOutcome>, descriptor: &BenchmarkDescriptor, result: Result<RunReport, MapfmClientError>) -> bool {
+        if let Err(error) = &result {
+            self.notify(Event::BenchmarkFailed { descriptor, error });
         }
 
-        Ok(())
+        let go_on = result.is_ok() || self.error_policy == ErrorPolicy::Continue;
+        outcomes.push(BenchmarkOutcome { descriptor: descriptor.clone(), result });
+        go_on
     }
 
-    pub fn run(&self, make_attempt: bool) -> Result<(), MapfmClientError> {
+    /// Runs every benchmark, returning what came of each of them in order. When a benchmark
+    /// fails, the [`ErrorPolicy`] decides whether the ones after it are still run; those
+    /// which aren't have no outcome. Only fails when no benchmark can be run at all, for
+    /// example because the token is invalid.
+    ///
+    /// When `make_attempt` is set, an attempt is started for each benchmark and
+    /// the solutions are submitted to it.
+    pub fn run_each(&self, make_attempt: bool) -> Result<Vec<BenchmarkOutcome>, MapfmClientError> {
+        if self.token_validation {
+            self.validate_token()?;
+        }
+        let mut outcomes = Vec::new();
+
         for descriptor in &self.benchmark_descriptors {
-            if make_attempt {
-                let (problems, id) = self.start_attempt(descriptor)?;
+            let result = self.run_descriptor(descriptor, make_attempt);
+            if !self.record_outcome(&mut outcomes, descriptor, result) {
+                break;
+            }
+        }
+
+        Ok(outcomes)
+    }
 
-                let solutions = self.run_benchmark(problems);
-                self.submit_solutions(descriptor, solutions, id)?;
-            } else {
-                let problems = self.get_benchmark(descriptor)?;
+    /// Runs every benchmark, returning a report of the run for each of them in order.
+    /// When `make_attempt` is set, an attempt is started for each benchmark and
+    /// the solutions are submitted to it.
+    ///
+    /// Fails with the error of the first benchmark which failed. See [`run_each`](Self::run_each)
+    /// for the outcome of every benchmark.
+    pub fn run(&self, make_attempt: bool) -> Result<Vec<RunReport>, MapfmClientError> {
+        self.run_each(make_attempt)?.into_iter().map(|i| i.result).collect()
+    }
+}
+
+impl MapfBenchmarker<HttpTransport> {
+    /// Solves the problems on tokio's blocking thread pool, so solving doesn't stall
+    /// other tasks running on the same runtime.
+    async fn run_benchmark_async(solving: Solving, problems: Vec<Problem>) -> Vec<SolveResult> {
+        tokio::task::spawn_blocking(move || solving.run(problems, |_, _| {}))
+            .await
+            .unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic()))
+    }
+
+    async fn submit_solutions_async(&self, descriptor: &BenchmarkDescriptor, solutions: &[SolveResult], id: i64) -> Result<(), MapfmClientError> {
+        let indices: Vec<_> = (0..solutions.len()).collect();
+        self.sending(descriptor, id, &indices, || submission_body(descriptor, solutions));
+
+        self.submitted(id, &indices, None, self.transport.submit_solutions_async(descriptor, id, solutions).await)
+    }
+
+    async fn submit_batch_async(&self, descriptor: &BenchmarkDescriptor, batch: &[(usize, SolveResult)], id: i64, checkpoint: Option<&Checkpointer>) -> Result<(), MapfmClientError> {
+        let indices: Vec<_> = batch.iter().map(|(index, _)| *index).collect();
+        self.sending(descriptor, id, &indices, || batch_submission_body(descriptor, batch));
+
+        self.submitted(id, &indices, checkpoint, self.transport.submit_batch_async(descriptor, id, batch).await)
+    }
 
-                self.run_benchmark(problems);
+    async fn run_benchmark_streaming_async(&self, descriptor: &BenchmarkDescriptor, problems: Vec<Problem>, id: i64, batch_size: usize, checkpoint: Option<Arc<Checkpointer>>, artifacts: Option<Artifacts>) -> Result<Vec<SolveResult>, MapfmClientError> {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let solving = self.solving(checkpoint.clone(), artifacts);
+        let solving = tokio::task::spawn_blocking(move || solving.run(problems, move |index, res| {
+            let _ = tx.send((index, res.clone()));
+        }));
+
+        let submitting = async move {
+            let mut pending = Pending::new(batch_size);
+            while let Some(res) = rx.recv().await {
+                if pending.push(res) {
+                    pending.submitted(self.submit_batch_async(descriptor, &pending.results, id, checkpoint.as_deref()).await)?;
+                }
             }
+
+            if !pending.results.is_empty() {
+                self.submit_batch_async(descriptor, &pending.results, id, checkpoint.as_deref()).await?;
+            }
+            Ok(())
+        };
+
+        let submitted = submitting.await;
+        let results = solving.await.unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic()));
+        submitted?;
+
+        Ok(results)
+    }
+
+    async fn run_attempt_async(&self, descriptor: &BenchmarkDescriptor, problems: Vec<Problem>, id: i64) -> Result<Vec<SolveResult>, MapfmClientError> {
+        let checkpoint = self.create_checkpoint(descriptor, id, &problems)?;
+        let artifacts = self.create_artifacts(descriptor, Some(id), &problems)?;
+
+        let results = if let Some(batch_size) = self.stream_batch_size {
+            self.run_benchmark_streaming_async(descriptor, problems, id, batch_size, checkpoint.clone(), artifacts).await?
+        } else {
+            let results = Self::run_benchmark_async(self.solving(checkpoint.clone(), artifacts), problems).await;
+            self.submit_solutions_async(descriptor, &results, id).await?;
+            results
+        };
+
+        if let Some(checkpoint) = checkpoint {
+            checkpoint.remove().map_err(MapfmClientError::Checkpoint)?;
         }
 
-        Ok(())
+        Ok(results)
     }
-}
 
-#[derive(Serialize)]
-struct ProgressiveParams {
-    num_agents: usize,
-    num_teams: usize,
-    max_diff: usize,
+    async fn run_descriptor_async(&self, descriptor: &BenchmarkDescriptor, make_attempt: bool) -> Result<RunReport, MapfmClientError> {
+        let report = if make_attempt {
+            let (problems, id) = self.transport.start_attempt_async(&self.request(descriptor)).await?;
+            self.notify(Event::AttemptStarted { descriptor, attempt_id: Some(id), num_problems: problems.len() });
 
-    starts: Vec<MarkedCoordinate>,
-    goals: Vec<MarkedCoordinate>,
-}
+            let results = self.run_attempt_async(descriptor, problems, id).await?;
+            RunReport::new(Some(descriptor), Some(id), &results)
+        } else {
+            let problems = self.transport.get_benchmark_async(&self.request(descriptor)).await?;
+            self.notify(Event::AttemptStarted { descriptor, attempt_id: None, num_problems: problems.len() });
 
-#[derive(Serialize)]
-struct SerializedPath {
-    route: Vec<Coordinate>
-}
+            let artifacts = self.create_artifacts(descriptor, None, &problems)?;
+            let results = Self::run_benchmark_async(self.solving(None, artifacts), problems).await;
+            RunReport::new(Some(descriptor), None, &results)
+        };
 
-#[derive(Serialize)]
-struct SerializedSolutionData {
-    paths: Vec<SerializedPath>
-}
+        self.notify(Event::BenchmarkFinished { report: &report });
+        Ok(report)
+    }
 
-#[derive(Serialize)]
-struct SerializedSolution {
-    time: u128,
-    solution: SerializedSolutionData,
-    progressive_params: Option<ProgressiveParams>
-}
+    /// Async version of [`validate_token`](Self::validate_token). Must be called from within a tokio runtime.
+    pub async fn validate_token_async(&self) -> Result<(), MapfmClientError> {
+        let descriptor = self.benchmark_descriptors.first().ok_or(MapfmClientError::NoBenchmarks)?;
+
+        self.transport.validate_async(&self.request(descriptor)).await
+    }
+
+    /// Async version of [`run_each`](Self::run_each). Must be called from within a tokio runtime.
+    pub async fn run_each_async(&self, make_attempt: bool) -> Result<Vec<BenchmarkOutcome>, MapfmClientError> {
+        if self.token_validation {
+            self.validate_token_async().await?;
+        }
+        let mut outcomes = Vec::new();
+
+        for descriptor in &self.benchmark_descriptors {
+            let result = self.run_descriptor_async(descriptor, make_attempt).await;
+            if !self.record_outcome(&mut outcomes, descriptor, result) {
+                break;
+            }
+        }
+
+        Ok(outcomes)
+    }
 
-#[derive(Serialize)]
-struct SubmitSolutionData {
-    solutions: Vec<SerializedSolution>,
-    benchmark: usize,
-    progressive: bool,
+    /// Async version of [`run`](Self::run). Must be called from within a tokio runtime.
+    pub async fn run_async(&self, make_attempt: bool) -> Result<Vec<RunReport>, MapfmClientError> {
+        self.run_each_async(make_attempt).await?.into_iter().map(|i| i.result).collect()
+    }
 }
 
-#[derive(Deserialize)]
-struct SerializedProblemList {
+#[derive(Serialize, Deserialize)]
+pub(crate) struct SerializedProblemList {
     benchmarks: Vec<SerializedProblem>,
 
     #[serde(default)]
-    attempt_id: i64,
+    pub(crate) attempt_id: i64,
 }
 
-#[derive(Deserialize)]
-struct SerializedProblem {
+impl SerializedProblemList {
+    pub fn problems(&self) -> Vec<Problem> {
+        self.benchmarks.iter().map(|i| i.to_problem()).collect()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct SerializedProblem {
     width: usize,
     height: usize,
     grid: Vec<Vec<i64>>,
@@ -332,6 +816,16 @@ struct SerializedProblem {
 }
 
 impl SerializedProblem {
+    pub fn from_problem(problem: &Problem) -> Self {
+        Self {
+            width: problem.grid.width(),
+            height: problem.grid.height(),
+            grid: problem.grid.to_int_vecs(),
+            starts: problem.starts.clone(),
+            goals: problem.goals.clone(),
+        }
+    }
+
     pub fn to_problem(&self) -> Problem {
         Problem {
             grid: Grid::from_int_vecs(self.width, self.height, self.grid.clone()),
@@ -341,55 +835,769 @@ impl SerializedProblem {
     }
 }
 
-#[derive(Serialize)]
-struct GetBenchmarkData {
-    algorithm: String,
-    version: String,
-    debug: bool,
-    progressive: bool,
-    progressive_description: Option<Box<ProgressiveDescriptor>>,
-    create_attempt: bool,
-}
-
-
 #[cfg(test)]
 mod tests {
-    use crate::client::{BenchmarkDescriptor, MapfBenchmarker};
+    use crate::client::{BenchmarkDescriptor, ErrorPolicy, MapfBenchmarker, MapfmClientError, ProgressiveDescriptor};
+    use crate::checkpoint::Checkpointer;
+    use crate::generator::ProgressiveGenerator;
     use crate::problem::Problem;
     use crate::solution::Solution;
     use crate::coordinate::Coordinate;
+    use crate::grid::Grid;
+    use crate::runner::SolveResult;
+    use crate::transport::{BenchmarkRequest, ProblemSource, SolutionSink};
+    use crate::mock::MockServer;
+    use crate::http::HttpTransport;
+    use crate::builder::MapfBenchmarkerBuilder;
+    use crate::cache::BenchmarkCache;
+    use crate::http_config::HttpConfig;
+    use crate::observer::Event;
+    use crate::report::ProblemStatus;
+    use crate::solver::SolverOutcome;
+    use crate::testing::TempPath;
+    use crate::token::Token;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use crate::retry::RetryPolicy;
+    use serde_json::{json, Value};
+    use std::sync::Mutex;
+    use std::time::Duration;
+    use std::future::Future;
+
+    fn test_problem() -> Problem {
+        Problem {
+            grid: Grid::from_int_vecs(3, 2, vec![vec![1, 1, 1], vec![0, 0, 0]]),
+            starts: vec![],
+            goals: vec![],
+        }
+    }
+
+    struct MemoryTransport {
+        problems: Vec<Problem>,
+        submitted: Mutex<Vec<(usize, i64, usize)>>,
+        /// How long submitting takes.
+        delay: Duration,
+        /// The benchmark for which submitting fails.
+        failing: Option<usize>,
+        /// The errors the next batches fail with, one for each.
+        batch_failures: Mutex<Vec<MapfmClientError>>,
+    }
+
+    impl MemoryTransport {
+        fn new(problems: Vec<Problem>) -> Self {
+            Self {
+                problems,
+                submitted: Mutex::new(Vec::new()),
+                delay: Duration::default(),
+                failing: None,
+                batch_failures: Mutex::new(Vec::new()),
+            }
+        }
+
+        fn check_failing(&self, descriptor: &BenchmarkDescriptor) -> Result<(), MapfmClientError> {
+            match self.failing {
+                Some(i) if i == descriptor.identifier() => Err(MapfmClientError::Status(500, "failing".to_string())),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    impl ProblemSource for MemoryTransport {
+        fn get_benchmark(&self, _request: &BenchmarkRequest) -> Result<Vec<Problem>, MapfmClientError> {
+            Ok(self.problems.clone())
+        }
+
+        fn start_attempt(&self, _request: &BenchmarkRequest) -> Result<(Vec<Problem>, i64), MapfmClientError> {
+            Ok((self.problems.clone(), 42))
+        }
+    }
+
+    impl SolutionSink for MemoryTransport {
+        fn submit_solutions(&self, descriptor: &BenchmarkDescriptor, attempt_id: i64, solutions: &[SolveResult]) -> Result<(), MapfmClientError> {
+            self.check_failing(descriptor)?;
+            self.submitted.lock().unwrap().push((descriptor.identifier(), attempt_id, solutions.len()));
+            Ok(())
+        }
+
+        fn submit_batch(&self, descriptor: &BenchmarkDescriptor, attempt_id: i64, batch: &[(usize, SolveResult)]) -> Result<(), MapfmClientError> {
+            std::thread::sleep(self.delay);
+            self.check_failing(descriptor)?;
+            if let Some(e) = self.batch_failures.lock().unwrap().pop() {
+                return Err(e);
+            }
+            self.submitted.lock().unwrap().push((descriptor.identifier(), attempt_id, batch.len()));
+            Ok(())
+        }
+    }
 
     #[test]
-    #[cfg(not(feature = "cbindgen-on"))]
-    pub fn test() {
-        let benchmark = BenchmarkDescriptor::from_identifier(1);
-        let token = "Y2S9hyWDTbHC7cNl3kllKb3JB0EK";
-
-        fn test (problem: Problem) -> Solution {
-            dbg!(problem);
-
-            return Solution {
-                paths: vec![
-                    vec![
-                        Coordinate::new(2, 1),
-                        Coordinate::new(1, 1),
-                        Coordinate::new(0, 1),
-                    ]
+    pub fn test_transport() {
+        fn solve(_problem: Problem) -> Solution {
+            Solution { paths: vec![] }
+        }
+
+        let transport = MemoryTransport::new(vec![test_problem(), test_problem()]);
+
+        let bm = MapfBenchmarker::builder("test", solve)
+            .with_benchmarks(vec![BenchmarkDescriptor::from_identifier(1), BenchmarkDescriptor::from_identifier(2)])
+            .build_with_transport(transport)
+            .unwrap();
+
+        bm.run(true).unwrap();
+        assert_eq!(*bm.transport().submitted.lock().unwrap(), vec![(1, 42, 2), (2, 42, 2)]);
+        assert_eq!(bm.acknowledged(42), vec![0, 1]);
+    }
+
+    #[test]
+    pub fn test_streaming_errors() {
+        let bm = |failures| MapfBenchmarker::builder("test", test_solver)
+            .with_benchmark(BenchmarkDescriptor::from_identifier(1))
+            .with_streaming_submission(1)
+            .build_with_transport(MemoryTransport {
+                batch_failures: Mutex::new(failures),
+                ..MemoryTransport::new(vec![test_problem(); 3])
+            })
+            .unwrap();
+
+        // a batch the server didn't get goes along with the next one
+        let bm1 = bm(vec![MapfmClientError::RateLimited("busy".to_string())]);
+        assert_eq!(bm1.run(true).unwrap()[0].solved(), 3);
+        assert_eq!(bm1.acknowledged(42), vec![0, 1, 2]);
+        let sizes: Vec<_> = bm1.transport().submitted.lock().unwrap().iter().map(|(_, _, len)| *len).collect();
+        assert_eq!(sizes.iter().sum::<usize>(), 3);
+        assert_eq!(sizes.len(), 2);
+
+        // anything else ends the stream, without sending the batch again
+        let bm2 = bm(vec![MapfmClientError::Rejected("attempt closed".to_string())]);
+        assert!(matches!(bm2.run(true), Err(MapfmClientError::Rejected(_))));
+        assert!(bm2.transport().submitted.lock().unwrap().is_empty());
+        assert!(bm2.acknowledged(42).is_empty());
+    }
+
+    #[test]
+    pub fn test_checkpoint_per_benchmark() {
+        let dir = TempPath::new("checkpoints");
+        let builder = || MapfBenchmarker::builder("test", test_solver)
+            .with_benchmarks(vec![BenchmarkDescriptor::from_identifier(1), BenchmarkDescriptor::from_identifier(2)])
+            .with_checkpoint(&dir)
+            .with_error_policy(ErrorPolicy::Continue);
+
+        let transport = MemoryTransport {
+            failing: Some(1),
+            ..MemoryTransport::new(vec![test_problem(), test_problem()])
+        };
+        let bm = builder().build_with_transport(transport).unwrap();
+        let outcomes = bm.run_each(true).unwrap();
+        assert_eq!(outcomes.iter().map(|i| i.result.is_ok()).collect::<Vec<_>>(), vec![false, true]);
+
+        // the second benchmark leaves the state of the first one alone
+        let failed = bm.checkpoint_file(&BenchmarkDescriptor::from_identifier(1)).unwrap();
+        assert!(failed.exists());
+        assert!(!bm.checkpoint_file(&BenchmarkDescriptor::from_identifier(2)).unwrap().exists());
+
+        let bm = builder().build_with_transport(MemoryTransport::new(vec![test_problem(), test_problem()])).unwrap();
+        assert_eq!(bm.resume(&failed).unwrap().solved(), 2);
+        assert_eq!(*bm.transport().submitted.lock().unwrap(), vec![(1, 42, 2)]);
+        assert!(!failed.exists());
+    }
+
+    #[test]
+    pub fn test_streaming_submission() {
+        let transport = MemoryTransport {
+            delay: Duration::from_millis(200),
+            ..MemoryTransport::new(vec![test_problem(); 4])
+        };
+
+        let bm = MapfBenchmarker::builder("test", test_solver)
+            .with_benchmark(BenchmarkDescriptor::from_identifier(1))
+            .with_streaming_submission(1)
+            .with_benchmark_time_limit(Duration::from_millis(500))
+            .build_with_transport(transport)
+            .unwrap();
+
+        // submitting takes longer than the time limit, but doesn't count towards it
+        let reports = bm.run(true).unwrap();
+        assert_eq!(reports[0].solved(), 4);
+        assert_eq!(bm.acknowledged(42), vec![0, 1, 2, 3]);
+        assert_eq!(bm.transport().submitted.lock().unwrap().iter().map(|(_, _, len)| len).sum::<usize>(), 4);
+    }
+
+    #[test]
+    pub fn test_error_from_status() {
+        assert!(matches!(
+            MapfmClientError::from_status(401, r#"{"message": "invalid token"}"#.to_string()),
+            MapfmClientError::Unauthorized(m) if m == "invalid token"
+        ));
+        assert!(matches!(
+            MapfmClientError::from_status(404, "no such attempt".to_string()),
+            MapfmClientError::NotFound(m) if m == "no such attempt"
+        ));
+        assert!(matches!(
+            MapfmClientError::from_status(409, r#"{"error": "attempt closed"}"#.to_string()),
+            MapfmClientError::Rejected(m) if m == "attempt closed"
+        ));
+        assert!(matches!(
+            MapfmClientError::from_status(502, "".to_string()),
+            MapfmClientError::Status(502, m) if m.is_empty()
+        ));
+    }
+
+    fn test_solver(_problem: Problem) -> Solution {
+        Solution {
+            paths: vec![
+                vec![
+                    Coordinate::new(2, 1),
+                    Coordinate::new(1, 1),
+                    Coordinate::new(0, 1),
                 ]
-            }
+            ]
         }
+    }
+
+    /// A benchmarker running [`test_solver`] on a single benchmark of the mock `server`.
+    fn test_builder(server: &MockServer, token: &str, identifier: usize) -> MapfBenchmarkerBuilder {
+        MapfBenchmarker::builder("test", test_solver)
+            .with_token(token)
+            .with_benchmark(BenchmarkDescriptor::from_identifier(identifier))
+            .with_version("test")
+            .with_base_url(&server.url())
+    }
+
+    fn test_benchmarker(server: &MockServer, token: &str, identifier: usize) -> MapfBenchmarker {
+        test_builder(server, token, identifier).build().unwrap()
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
 
-        let bm = MapfBenchmarker::new(
-            token,
-            vec![benchmark],
-            "test",
-            "test",
-            true,
-            test,
-            None,
+    #[test]
+    pub fn test() {
+        let server = MockServer::start().with_benchmark(1, vec![test_problem()]);
+        let benchmark = BenchmarkDescriptor::from_identifier(1);
+        let token = "test-token";
+
+        let bm = MapfBenchmarker::builder("test", test_solver)
+            .with_token(token)
+            .with_benchmark(benchmark)
+            .with_version("test")
+            .with_base_url(&server.url())
+            .build()
+            .unwrap();
+
+        let reports = bm.run(true).unwrap();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].attempt_id, Some(1));
+        assert_eq!(reports[0].solved(), 1);
+        assert_eq!(reports[0].problems[0].makespan, Some(2));
+
+        let requests = server.benchmark_requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].id, 1);
+        assert_eq!(requests[0].token.as_deref(), Some(token));
+        assert_eq!(requests[0].body["create_attempt"], true);
+
+        let submissions = server.submissions();
+        assert_eq!(submissions.len(), 1);
+        assert_eq!(submissions[0].id, 1);
+        assert_eq!(submissions[0].body["benchmark"], 1);
+        assert_eq!(
+            submissions[0].body["solutions"][0]["solution"]["paths"][0]["route"],
+            json!([{"x": 2, "y": 1}, {"x": 1, "y": 1}, {"x": 0, "y": 1}])
         );
+    }
+
+    #[test]
+    pub fn test_unsolved() {
+        let server = MockServer::start().with_benchmark(1, vec![test_problem(), test_problem(), test_problem()]);
+        let calls = AtomicUsize::new(0);
+        let solver = move |problem: Problem| match calls.fetch_add(1, Ordering::SeqCst) {
+            0 => SolverOutcome::Unsolvable,
+            1 => SolverOutcome::Error("out of memory".to_string()),
+            _ => test_solver(problem).into(),
+        };
+
+        let reports = MapfBenchmarker::builder("test", solver)
+            .with_token("test-token")
+            .with_benchmark(BenchmarkDescriptor::from_identifier(1))
+            .with_base_url(&server.url())
+            .build()
+            .unwrap()
+            .run(true)
+            .unwrap();
+
+        let statuses: Vec<_> = reports[0].problems.iter().map(|i| i.status).collect();
+        assert_eq!(statuses, vec![ProblemStatus::Unsolvable, ProblemStatus::Errored, ProblemStatus::Solved]);
+        let solutions = &server.submissions()[0].body["solutions"];
+        assert_eq!(solutions[0]["solution"], Value::Null);
+        assert_eq!(solutions[1]["solution"], Value::Null);
+        assert!(solutions[2]["solution"]["paths"].is_array());
+    }
+
+    #[test]
+    pub fn test_failure_cutoff() {
+        let descriptor = ProgressiveDescriptor::new(3, 1, 1, 0);
+        let problems = ProgressiveGenerator::new(test_problem().grid, 1)
+            .with_instances_per_size(2)
+            .generate(&descriptor)
+            .unwrap();
+        let server = MockServer::start().with_benchmark(1, problems);
+        let solver = |problem: Problem| match problem.starts.len() {
+            1 => Some(test_solver(problem)),
+            _ => None,
+        };
+
+        let reports = MapfBenchmarker::builder("test", solver)
+            .with_token("test-token")
+            .with_benchmark(BenchmarkDescriptor::new(1, Some(descriptor)))
+            .with_base_url(&server.url())
+            .with_failure_cutoff(2)
+            .build()
+            .unwrap()
+            .run(true)
+            .unwrap();
+
+        let statuses: Vec<_> = reports[0].problems.iter().map(|i| i.status).collect();
+        assert_eq!(statuses, vec![
+            ProblemStatus::Solved, ProblemStatus::Solved,
+            ProblemStatus::Unsolvable, ProblemStatus::Unsolvable,
+            ProblemStatus::Skipped, ProblemStatus::Skipped,
+        ]);
+
+        let solutions = server.submissions()[0].body["solutions"].as_array().unwrap().clone();
+        let agents: Vec<_> = solutions.iter().map(|i| i["progressive_params"]["num_agents"].as_u64().unwrap()).collect();
+        assert_eq!(agents, vec![1, 1, 2, 2, 3, 3]);
+        assert!(solutions[1]["solution"].is_object());
+        assert_eq!(solutions[4]["solution"], Value::Null);
+    }
+
+    #[test]
+    pub fn test_failure_cutoff_single_instances() {
+        let descriptor = ProgressiveDescriptor::new(10, 1, 1, 0);
+        let problems = ProgressiveGenerator::new(Grid::from_int_vecs(5, 2, vec![vec![0; 5]; 2]), 1)
+            .generate(&descriptor)
+            .unwrap();
+        let solver = |problem: Problem| match problem.starts.len() {
+            1 | 2 => Some(test_solver(problem)),
+            _ => None,
+        };
+
+        let reports = MapfBenchmarker::builder("test", solver)
+            .with_benchmark(BenchmarkDescriptor::new(1, Some(descriptor)))
+            .with_failure_cutoff(2)
+            .build_with_transport(MemoryTransport::new(problems))
+            .unwrap()
+            .run(true)
+            .unwrap();
+
+        // every agent count fails only once, but the failures follow each other
+        let statuses: Vec<_> = reports[0].problems.iter().map(|i| i.status).collect();
+        let mut expected = vec![ProblemStatus::Solved, ProblemStatus::Solved, ProblemStatus::Unsolvable, ProblemStatus::Unsolvable];
+        expected.extend(vec![ProblemStatus::Skipped; 6]);
+        assert_eq!(statuses, expected);
+    }
+
+    #[test]
+    pub fn test_resume_failure_cutoff() {
+        let path = TempPath::new("resume.json");
+        let descriptor = ProgressiveDescriptor::new(3, 1, 1, 0);
+        let problems = ProgressiveGenerator::new(test_problem().grid, 1)
+            .with_instances_per_size(2)
+            .generate(&descriptor)
+            .unwrap();
+        let benchmark = BenchmarkDescriptor::new(1, Some(descriptor));
+
+        // stopped after failing twice on problems with 2 agents
+        let checkpoint = Checkpointer::create(&path, &benchmark, 42, &problems).unwrap();
+        for (index, outcome) in vec![SolverOutcome::Solved(Solution { paths: vec![] }), SolverOutcome::Unsolvable, SolverOutcome::Unsolvable].into_iter().enumerate() {
+            checkpoint.record(index + 1, &(outcome, problems[index + 1].clone(), Duration::from_millis(1)));
+        }
 
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let report = MapfBenchmarker::builder("test", move |problem: Problem| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            test_solver(problem)
+        })
+            .with_benchmark(benchmark)
+            .with_failure_cutoff(2)
+            .build_with_transport(MemoryTransport::new(problems))
+            .unwrap()
+            .resume(&path)
+            .unwrap();
+
+        let statuses: Vec<_> = report.problems.iter().map(|i| i.status).collect();
+        assert_eq!(statuses, vec![
+            ProblemStatus::Solved, ProblemStatus::Solved,
+            ProblemStatus::Unsolvable, ProblemStatus::Unsolvable,
+            ProblemStatus::Skipped, ProblemStatus::Skipped,
+        ]);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    pub fn test_error_policy() {
+        let server = MockServer::start()
+            .with_benchmark(1, vec![test_problem()])
+            .with_benchmark(3, vec![test_problem()]);
+        let bm = |policy| MapfBenchmarker::builder("test", test_solver)
+            .with_token("test-token")
+            .with_benchmarks((1..=3).map(BenchmarkDescriptor::from_identifier))
+            .with_base_url(&server.url())
+            .with_retry_policy(RetryPolicy::none())
+            .with_error_policy(policy)
+            .build()
+            .unwrap();
+
+        let outcomes = bm(ErrorPolicy::FailFast).run_each(true).unwrap();
+        assert_eq!(outcomes.len(), 2);
+        assert!(matches!(outcomes[1].result, Err(MapfmClientError::BenchmarkNotFound(_))));
+        assert!(matches!(bm(ErrorPolicy::FailFast).run(true), Err(MapfmClientError::BenchmarkNotFound(_))));
+
+        let outcomes = bm(ErrorPolicy::Continue).run_each(true).unwrap();
+        let identifiers: Vec<_> = outcomes.iter().map(|i| (i.descriptor.identifier(), i.result.is_ok())).collect();
+        assert_eq!(identifiers, vec![(1, true), (2, false), (3, true)]);
+    }
+
+    #[test]
+    pub fn test_artifacts() {
+        let dir = TempPath::new("client-artifacts");
+        let server = MockServer::start().with_benchmark(1, vec![test_problem(), test_problem()]);
+
+        let bm = test_builder(&server, "test-token", 1).with_artifacts(&dir).build().unwrap();
         bm.run(true).unwrap();
+
+        let attempt = dir.join("1").join("attempt-1");
+        let report = bm.run_local(attempt.join("problems")).unwrap();
+        assert_eq!((&report.descriptor, report.attempt_id, report.solved()), (&None, None, 2));
+        assert!(attempt.join("solutions/00001.json").exists());
+        let submitted: Value = serde_json::from_slice(&std::fs::read(attempt.join("submissions/00000.json")).unwrap()).unwrap();
+        assert_eq!(submitted, server.submissions()[0].body);
+    }
+
+    #[test]
+    pub fn test_observer() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let observed = events.clone();
+
+        let transport = MemoryTransport::new(vec![test_problem(), test_problem()]);
+        let bm = MapfBenchmarker::builder("test", test_solver)
+            .with_benchmark(BenchmarkDescriptor::from_identifier(1))
+            .with_observer(move |event: &Event| observed.lock().unwrap().push(match event {
+                Event::AttemptStarted { attempt_id, num_problems, .. } => format!("attempt {:?} {}", attempt_id, num_problems),
+                Event::ProblemStarted { index } => format!("start {}", index),
+                Event::ProblemSolved { index, .. } => format!("solved {}", index),
+                Event::SubmissionSent { indices, .. } => format!("sent {:?}", indices),
+                Event::SubmissionAcknowledged { indices, .. } => format!("acknowledged {:?}", indices),
+                Event::BenchmarkFinished { report } => format!("finished {}", report.solved()),
+                event => format!("{:?}", event),
+            }))
+            .build_with_transport(transport)
+            .unwrap();
+
+        bm.run(true).unwrap();
+        assert_eq!(*events.lock().unwrap(), vec![
+            "attempt Some(42) 2", "start 0", "solved 0", "start 1", "solved 1",
+            "sent [0, 1]", "acknowledged [0, 1]", "finished 2",
+        ]);
+    }
+
+    #[test]
+    pub fn test_record_replay() {
+        let cassette = TempPath::new("session.json");
+        let server = MockServer::start().with_benchmark(1, vec![test_problem()]);
+
+        test_builder(&server, "test-token", 1)
+            .with_recording(&cassette)
+            .build()
+            .unwrap()
+            .run(true)
+            .unwrap();
+        drop(server);
+        assert!(!std::fs::read_to_string(&cassette).unwrap().contains("test-token"));
+
+        let replay = |identifier| MapfBenchmarker::builder("test", test_solver)
+            .with_benchmark(BenchmarkDescriptor::from_identifier(identifier))
+            .build_with_transport(HttpTransport::replay(&cassette).unwrap())
+            .unwrap();
+        replay(1).run(true).unwrap();
+        assert!(matches!(replay(2).run(true), Err(MapfmClientError::Cassette(_))));
+    }
+
+    #[test]
+    pub fn test_list_benchmarks() {
+        let server = MockServer::start()
+            .with_benchmark(3, vec![test_problem(), test_problem()])
+            .with_benchmark(1, vec![test_problem()]);
+        let transport = HttpTransport::new("test-token", Some(&server.url()));
+
+        let benchmarks = transport.list_benchmarks().unwrap();
+        assert_eq!(benchmarks.iter().map(|i| i.id).collect::<Vec<_>>(), vec![1, 3]);
+        assert_eq!(benchmarks[1].descriptor(), BenchmarkDescriptor::from_identifier(3));
+
+        let info = transport.benchmark_info(3).unwrap();
+        assert_eq!((info.width, info.height, info.num_problems), (3, 2, 2));
+        assert!(matches!(transport.benchmark_info(2), Err(MapfmClientError::BenchmarkNotFound(_))));
+
+        block_on(async {
+            assert_eq!(transport.list_benchmarks_async().await.unwrap(), benchmarks);
+            assert_eq!(transport.benchmark_info_async(3).await.unwrap(), info);
+            assert!(matches!(transport.benchmark_info_async(2).await, Err(MapfmClientError::BenchmarkNotFound(_))));
+        });
+    }
+
+    #[test]
+    pub fn test_attempt_results() {
+        let server = MockServer::start().with_benchmark(1, vec![test_problem()]);
+        let bm = MapfBenchmarker::builder("test", test_solver)
+            .with_token("test-token")
+            .with_benchmark(BenchmarkDescriptor::from_identifier(1))
+            .with_version("v1")
+            .with_debug(false)
+            .with_base_url(&server.url())
+            .build()
+            .unwrap();
+        bm.run(true).unwrap();
+
+        let transport = bm.transport();
+        let attempts = transport.list_attempts("test", "v1").unwrap();
+        assert_eq!(attempts.len(), 1);
+        assert_eq!((attempts[0].id, attempts[0].benchmark), (1, 1));
+        assert!(transport.list_attempts("test", "v2").unwrap().is_empty());
+
+        assert!(matches!(transport.attempt_results(99), Err(MapfmClientError::NotFound(_))));
+        let results = transport.attempt_results(attempts[0].id).unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].valid);
+        assert_eq!(results[0].cost, Some(3));
+
+        let leaderboard = transport.leaderboard(1).unwrap();
+        assert_eq!(leaderboard.len(), 1);
+        assert_eq!((leaderboard[0].rank, leaderboard[0].attempt, leaderboard[0].solved), (1, 1, 1));
+
+        block_on(async {
+            assert_eq!(transport.list_attempts_async("test", "v1").await.unwrap(), attempts);
+            assert_eq!(transport.attempt_results_async(attempts[0].id).await.unwrap(), results);
+            assert!(matches!(transport.attempt_results_async(99).await, Err(MapfmClientError::NotFound(_))));
+            assert_eq!(transport.leaderboard_async(1).await.unwrap(), leaderboard);
+        });
+    }
+
+    #[test]
+    pub fn test_run_async() {
+        let dir = TempPath::new("client-async");
+        let server = MockServer::start().with_benchmark(1, vec![test_problem(), test_problem()]).with_token("test-token");
+        let bm = test_builder(&server, "test-token", 1)
+            .with_benchmark(BenchmarkDescriptor::from_identifier(2))
+            .with_error_policy(ErrorPolicy::Continue)
+            .with_token_validation(true)
+            .with_artifacts(&dir)
+            .build()
+            .unwrap();
+
+        let outcomes = block_on(async {
+            bm.validate_token_async().await.unwrap();
+            assert!(matches!(bm.run_async(false).await, Err(MapfmClientError::BenchmarkNotFound(_))));
+            bm.run_each_async(true).await.unwrap()
+        });
+        let report = outcomes[0].result.as_ref().unwrap();
+        let id = report.attempt_id.unwrap();
+        assert_eq!(report.solved(), 2);
+        assert!(matches!(outcomes[1].result, Err(MapfmClientError::BenchmarkNotFound(_))));
+        assert_eq!(server.submissions().len(), 1);
+        assert_eq!(bm.acknowledged(id), vec![0, 1]);
+        assert!(dir.join("1").join(format!("attempt-{}", id)).join("solutions/00001.json").exists());
+
+        let wrong = test_benchmarker(&server, "wrong-token", 1);
+        assert!(matches!(block_on(wrong.validate_token_async()), Err(MapfmClientError::Unauthorized(_))));
+    }
+
+    #[test]
+    pub fn test_streaming_async() {
+        let dir = TempPath::new("streaming-async");
+        let server = MockServer::start().with_benchmark(1, vec![test_problem(); 3]);
+        let bm = test_builder(&server, "test-token", 1)
+            .with_retry_policy(RetryPolicy::none())
+            .with_streaming_submission(1)
+            .with_artifacts(&dir)
+            .build()
+            .unwrap();
+
+        let report = block_on(bm.run_async(true)).unwrap().remove(0);
+        let id = report.attempt_id.unwrap();
+        assert_eq!(report.solved(), 3);
+        assert_eq!(server.submissions().len(), 3);
+        assert_eq!(bm.acknowledged(id), vec![0, 1, 2]);
+        for index in 0..3 {
+            assert!(dir.join("1").join(format!("attempt-{}", id)).join(format!("solutions/{:05}.json", index)).exists());
+        }
+
+        // a batch the server was too busy for goes along with the next one
+        server.fail_next_at("/api/solutions/submit/", 503, "");
+        let id = block_on(bm.run_async(true)).unwrap()[0].attempt_id.unwrap();
+        assert_eq!(server.submissions().len(), 5);
+        assert_eq!(bm.acknowledged(id), vec![0, 1, 2]);
+
+        // but a rejected one ends the stream
+        server.fail_next_at("/api/solutions/submit/", 422, r#"{"message": "attempt closed"}"#);
+        assert!(matches!(block_on(bm.run_async(true)), Err(MapfmClientError::Rejected(m)) if m == "attempt closed"));
+        assert_eq!(server.submissions().len(), 5);
+    }
+
+    #[test]
+    pub fn test_token_validation() {
+        let server = MockServer::start().with_benchmark(1, vec![test_problem()]).with_token("right-token");
+        let bm = |token: &str| MapfBenchmarker::builder("test", test_solver)
+            .with_token(token)
+            .with_token_validation(true)
+            .with_benchmark(BenchmarkDescriptor::from_identifier(1))
+            .with_base_url(&server.url())
+            .with_retry_policy(RetryPolicy::none())
+            .build()
+            .unwrap();
+
+        assert!(matches!(bm("wrong-token").run(true), Err(MapfmClientError::Unauthorized(_))));
+        assert!(server.benchmark_requests().is_empty());
+
+        server.fail_next(500, "server error for right-token");
+        let err = bm("right-token").run(true).unwrap_err();
+        assert!(!format!("{} {:?}", err, err).contains("right-token"));
+
+        bm("right-token").run(true).unwrap();
+        assert_eq!(server.submissions().len(), 1);
+        let attempts: Vec<_> = server.benchmark_requests().iter().map(|i| i.body["create_attempt"].clone()).collect();
+        assert_eq!(attempts, vec![false, true]);
+
+        // without validation, a wrong token is only found out when starting the attempt
+        assert!(matches!(test_benchmarker(&server, "wrong-token", 1).run(true), Err(MapfmClientError::Unauthorized(_))));
+        assert_eq!(server.benchmark_requests().len(), 2);
+    }
+
+    #[test]
+    pub fn test_cache_without_token() {
+        let server = MockServer::start().with_benchmark(1, vec![test_problem()]).with_benchmark(2, vec![test_problem()]);
+        let dir = TempPath::new("tokenless");
+        let cache = BenchmarkCache::new(&dir).prefer_cache(true);
+
+        // fill the cache with a token, as when running online before
+        test_builder(&server, "test-token", 1).with_cache(cache.clone()).build().unwrap().run(false).unwrap();
+        assert_eq!(server.benchmark_requests().len(), 1);
+
+        // a token in the environment or config file would be used instead
+        if Token::resolve(None).is_ok() {
+            return;
+        }
+        let bm = |identifier| MapfBenchmarker::builder("test", test_solver)
+            .with_benchmark(BenchmarkDescriptor::from_identifier(identifier))
+            .with_base_url(&server.url())
+            .with_cache(cache.clone())
+            .build()
+            .unwrap();
+        assert_eq!(bm(1).run(false).unwrap()[0].solved(), 1);
+        assert!(matches!(bm(1).run(true), Err(MapfmClientError::MissingToken)));
+        assert!(matches!(bm(2).run(false), Err(MapfmClientError::MissingToken)));
+        assert_eq!(server.benchmark_requests().len(), 1);
+        assert!(matches!(
+            MapfBenchmarker::builder("test", test_solver).with_benchmark(BenchmarkDescriptor::from_identifier(1)).build(),
+            Err(MapfmClientError::MissingToken)
+        ));
+    }
+
+    #[test]
+    pub fn test_http_config() {
+        let server = MockServer::start().with_benchmark(1, vec![test_problem()]);
+        let request = BenchmarkRequest {
+            descriptor: &BenchmarkDescriptor::from_identifier(1),
+            algorithm_name: "test",
+            version: "test",
+            debug: true,
+        };
+
+        let config = HttpConfig::new()
+            .with_user_agent("mapfm-test")
+            .with_header("X-Team", "a")
+            .with_timeout(Duration::from_millis(200));
+        let transport = HttpTransport::new("test-token", Some(&server.url())).with_http_config(&config).unwrap();
+        transport.get_benchmark(&request).unwrap();
+
+        let headers = &server.benchmark_requests()[0].headers;
+        assert_eq!(headers["user-agent"], "mapfm-test");
+        assert_eq!(headers["x-team"], "a");
+
+        server.set_delay(Duration::from_secs(1));
+        let transport = transport.with_retry_policy(RetryPolicy::none());
+        assert!(matches!(transport.get_benchmark(&request), Err(MapfmClientError::RequestError(e)) if e.is_timeout()));
+
+        assert!(matches!(
+            HttpTransport::new("test-token", None).with_http_config(&HttpConfig::new().with_header("X Team", "a")),
+            Err(MapfmClientError::ClientConfig(_))
+        ));
+    }
+
+    #[test]
+    pub fn test_submission_not_resent() {
+        let server = MockServer::start().with_benchmark(1, vec![test_problem()]);
+        let descriptor = BenchmarkDescriptor::from_identifier(1);
+        let request = BenchmarkRequest {
+            descriptor: &descriptor,
+            algorithm_name: "test",
+            version: "test",
+            debug: true,
+        };
+        let transport = HttpTransport::new("test-token", Some(&server.url()))
+            .with_http_config(&HttpConfig::new().with_timeout(Duration::from_millis(200)))
+            .unwrap()
+            .with_retry_policy(RetryPolicy::new(3).with_backoff(Duration::from_millis(1), Duration::from_millis(1)));
+
+        // the server handles every request, but answers after the client stopped waiting
+        server.set_delay(Duration::from_secs(1));
+        assert!(matches!(transport.get_benchmark(&request), Err(MapfmClientError::RequestError(e)) if e.is_timeout()));
+        assert_eq!(server.benchmark_requests().len(), 3);
+        assert!(matches!(transport.start_attempt(&request), Err(MapfmClientError::RequestError(e)) if e.is_timeout()));
+        assert_eq!(server.benchmark_requests().len(), 4);
+
+        let solutions = vec![(SolverOutcome::Unsolvable, test_problem(), Duration::from_millis(1))];
+        assert!(matches!(transport.submit_solutions(&descriptor, 1, &solutions), Err(MapfmClientError::RequestError(e)) if e.is_timeout()));
+        assert!(matches!(transport.submit_batch(&descriptor, 1, &[(0, solutions[0].clone())]), Err(MapfmClientError::RequestError(e)) if e.is_timeout()));
+        assert_eq!(server.submissions().len(), 2);
+
+        // a gateway timeout may come after the server stored the submission, but 503 means it didn't
+        server.set_delay(Duration::default());
+        server.fail_next(504, "");
+        assert!(matches!(transport.submit_solutions(&descriptor, 1, &solutions), Err(MapfmClientError::Status(504, _))));
+        assert_eq!(server.submissions().len(), 2);
+        server.fail_next(503, "");
+        transport.submit_solutions(&descriptor, 1, &solutions).unwrap();
+        assert_eq!(server.submissions().len(), 3);
+    }
+
+    #[test]
+    pub fn test_server_errors() {
+        let server = MockServer::start().with_benchmark(1, vec![test_problem()]);
+        let bm = |identifier| test_builder(&server, "test-token", identifier)
+            .with_retry_policy(RetryPolicy::new(2).with_backoff(Duration::from_millis(1), Duration::from_millis(1)))
+            .build()
+            .unwrap();
+
+        // a transient error is retried, and the delay doesn't change anything
+        server.set_delay(Duration::from_millis(10));
+        server.fail_next(503, "");
+        bm(1).run(true).unwrap();
+        assert_eq!(server.submissions().len(), 1);
+
+        assert!(matches!(bm(2).run(true), Err(MapfmClientError::BenchmarkNotFound(_))));
+
+        server.fail_next(401, r#"{"message": "invalid token"}"#);
+        assert!(matches!(
+            bm(1).run(true),
+            Err(MapfmClientError::Unauthorized(m)) if m == "invalid token"
+        ));
+        assert_eq!(server.submissions().len(), 1);
     }
 }
 
@@ -22,7 +22,7 @@ impl_op_ex!(+ |a: &Coordinate, b: &Coordinate| -> Coordinate { Coordinate {x: a.
 impl_op_ex!(- |a: &Coordinate, b: &Coordinate| -> Coordinate { Coordinate {x: a.x - b.x, y: a.y - b.y} });
 
 
-impl<'a, N: PrimInt> ops::Mul<N> for &'a Coordinate {
+impl<N: PrimInt> ops::Mul<N> for &Coordinate {
     type Output = Coordinate;
 
     fn mul(self, other: N) -> Self::Output {
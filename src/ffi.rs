@@ -1,20 +1,24 @@
 #![cfg(feature = "cbindgen-on")]
 
-use crate::{MapfBenchmarker, BenchmarkDescriptor, Problem, Coordinate, MarkedCoordinate};
+use crate::{MapfBenchmarker, BenchmarkDescriptor, Problem, Coordinate, MarkedCoordinate, Solver, SolverOutcome};
 use crate::solution::Solution;
 use std::ffi::CStr;
 use libc::c_char;
 
+/// A solver written in C. It is called for one problem at a time, on the thread
+/// calling `run_benchmark`, so it doesn't need to be thread-safe.
+#[derive(Clone, Copy)]
 pub struct CallBack {
     cb: extern "C" fn(&Problem) -> *mut Solution
 }
 
 impl CallBack {
-    pub fn call(&self, problem: Problem) -> Solution {
+    /// Calls the solver, which returns a null pointer for a problem it can't solve.
+    pub fn call(&self, problem: Problem) -> Option<Solution> {
         let s = (self.cb)(&problem);
 
         if s == std::ptr::null_mut() {
-            panic!("null solution")
+            return None;
         }
 
         let val = unsafe {
@@ -25,7 +29,13 @@ impl CallBack {
 
         drop(val);
 
-        return res;
+        return Some(res);
+    }
+}
+
+impl Solver for CallBack {
+    fn solve(&self, problem: Problem) -> SolverOutcome {
+        self.call(problem).into()
     }
 }
 
@@ -48,24 +58,25 @@ unsafe fn create_benchmarker_helper(
     solver: CallBack,
     base_url: *const c_char,
 ) -> Option<MapfBenchmarker> {
-    Some(MapfBenchmarker::new(
-        get_str(token)?,
-        [benchmark].to_vec(),
-        get_str(algorithm_name)?,
-        get_str(version)?,
-        debug,
-        solver,
-        if base_url.is_null() {
-            None
-        } else {
-            Some(get_str(base_url)?)
-        }
-    ))
+    let mut builder = MapfBenchmarker::builder(get_str(algorithm_name)?, solver)
+        .with_token(get_str(token)?)
+        .with_benchmark(benchmark)
+        .with_version(get_str(version)?)
+        .with_debug(debug)
+        // a C solver can't be assumed to be thread-safe, so it must only ever run on the
+        // calling thread: one worker, and no time limits, which would move it to a thread
+        .with_workers(1);
+    if !base_url.is_null() {
+        builder = builder.with_base_url(get_str(base_url)?);
+    }
+
+    builder.build().ok()
 }
 
 #[cfg(feature = "cbindgen-on")]
 #[no_mangle]
-/// Create a new benchmarker (note: should be freed with free_benchmarker)
+/// Create a new benchmarker (note: should be freed with free_benchmarker). The solver may return null for problems it cannot solve.
+/// The solver is called for one problem at a time, on the thread calling run_benchmark, so it doesn't need to be thread-safe
 pub unsafe extern "C" fn create_benchmarker(
     token: *const c_char,
     benchmark: *mut BenchmarkDescriptor,
@@ -0,0 +1,198 @@
+use crate::client::{BenchmarkDescriptor, MapfmClientError, ProgressiveDescriptor, SerializedProblem};
+use crate::coordinate::Coordinate;
+use crate::grid::Grid;
+use crate::marked::MarkedCoordinate;
+use crate::problem::Problem;
+use crate::runner::SolveResult;
+use crate::transport::{BenchmarkRequest, ProblemSource, SolutionSink};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::path::Path;
+use std::sync::atomic::{AtomicI64, Ordering};
+
+/// Generates the instances of a progressive benchmark locally, on a fixed map, so scaling
+/// experiments can be run without the server. Used as the transport of a
+/// [`MapfBenchmarker`](crate::MapfBenchmarker), it hands out, for every agent count from
+/// `min_agents` up to `max_agents`, problems with that many agents split over `num_teams`
+/// teams, whose sizes differ by at most `max_diff`.
+///
+/// Starts and goals are placed at random on free cells. The same seed, map and descriptor
+/// always give the same problems (with the same version of this crate). Solutions are
+/// not judged; they are only reported locally, in the [`RunReport`](crate::RunReport)s.
+pub struct ProgressiveGenerator {
+    grid: Grid,
+    seed: u64,
+    instances_per_size: usize,
+    attempts: AtomicI64,
+}
+
+impl ProgressiveGenerator {
+    pub fn new(grid: Grid, seed: u64) -> Self {
+        Self {
+            grid,
+            seed,
+            instances_per_size: 1,
+            attempts: AtomicI64::new(0),
+        }
+    }
+
+    /// A generator on the map of the problem in the file at `path`, in the same format
+    /// the server sends problems in. Its starts and goals are ignored.
+    pub fn from_file(path: impl AsRef<Path>, seed: u64) -> Result<Self, MapfmClientError> {
+        let contents = std::fs::read(path).map_err(MapfmClientError::LocalProblems)?;
+        let problem: SerializedProblem = serde_json::from_slice(&contents).map_err(MapfmClientError::JsonDecodeError)?;
+
+        Ok(Self::new(problem.to_problem().grid, seed))
+    }
+
+    /// The number of problems generated for every agent count. Defaults to 1.
+    pub fn with_instances_per_size(mut self, instances: usize) -> Self {
+        self.instances_per_size = instances;
+        self
+    }
+
+    /// All problems for `descriptor`, ordered by number of agents.
+    pub fn generate(&self, descriptor: &ProgressiveDescriptor) -> Result<Vec<Problem>, MapfmClientError> {
+        let free: Vec<_> = (0..self.grid.height() as i64)
+            .flat_map(|y| (0..self.grid.width() as i64).map(move |x| Coordinate::new(x, y)))
+            .filter(|&i| self.grid.wall_at(i) == Some(false))
+            .collect();
+        if descriptor.min_agents() > descriptor.max_agents() {
+            return Err(MapfmClientError::Generator(format!(
+                "the minimum number of agents ({}) is more than the maximum ({})",
+                descriptor.min_agents(),
+                descriptor.max_agents()
+            )));
+        }
+        if descriptor.max_agents() > free.len() {
+            return Err(MapfmClientError::Generator(format!(
+                "{} agents don't fit on a map with {} free cells",
+                descriptor.max_agents(),
+                free.len()
+            )));
+        }
+
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut problems = Vec::new();
+        for num_agents in descriptor.min_agents()..=descriptor.max_agents() {
+            for _ in 0..self.instances_per_size {
+                let teams = team_sizes(&mut rng, num_agents, descriptor.num_teams().max(1), descriptor.max_diff());
+                let colours: Vec<_> = teams.iter()
+                    .enumerate()
+                    .flat_map(|(colour, &size)| std::iter::repeat(colour as i64).take(size))
+                    .collect();
+                let place = |rng: &mut StdRng| -> Vec<_> {
+                    free.choose_multiple(rng, num_agents)
+                        .zip(&colours)
+                        .map(|(at, &colour)| MarkedCoordinate::new(*at, colour))
+                        .collect()
+                };
+
+                problems.push(Problem {
+                    grid: self.grid.clone(),
+                    starts: place(&mut rng),
+                    goals: place(&mut rng),
+                });
+            }
+        }
+
+        Ok(problems)
+    }
+
+    fn problems(&self, request: &BenchmarkRequest) -> Result<Vec<Problem>, MapfmClientError> {
+        match request.descriptor.progressive_descriptor() {
+            Some(descriptor) => self.generate(descriptor),
+            None => Err(MapfmClientError::Generator(format!(
+                "benchmark {} is not progressive, so it can't be generated locally",
+                request.descriptor.identifier()
+            ))),
+        }
+    }
+}
+
+/// Splits `num_agents` over `num_teams` teams at random, such that the largest and the
+/// smallest team differ by at most `max_diff` agents.
+fn team_sizes(rng: &mut impl Rng, num_agents: usize, num_teams: usize, max_diff: usize) -> Vec<usize> {
+    let mut sizes = vec![0; num_teams];
+    for _ in 0..num_agents {
+        sizes[rng.gen_range(0..num_teams)] += 1;
+    }
+
+    // agents which don't divide over the teams can't be split evenly
+    let max_diff = max_diff.max((num_agents % num_teams != 0) as usize);
+    loop {
+        let (largest, _) = sizes.iter().enumerate().max_by_key(|(_, &i)| i).unwrap();
+        let (smallest, _) = sizes.iter().enumerate().min_by_key(|(_, &i)| i).unwrap();
+        if sizes[largest] - sizes[smallest] <= max_diff {
+            return sizes;
+        }
+
+        sizes[largest] -= 1;
+        sizes[smallest] += 1;
+    }
+}
+
+impl ProblemSource for ProgressiveGenerator {
+    fn get_benchmark(&self, request: &BenchmarkRequest) -> Result<Vec<Problem>, MapfmClientError> {
+        self.problems(request)
+    }
+
+    fn start_attempt(&self, request: &BenchmarkRequest) -> Result<(Vec<Problem>, i64), MapfmClientError> {
+        let problems = self.problems(request)?;
+        Ok((problems, self.attempts.fetch_add(1, Ordering::SeqCst) + 1))
+    }
+}
+
+impl SolutionSink for ProgressiveGenerator {
+    fn submit_solutions(&self, _: &BenchmarkDescriptor, _: i64, _: &[SolveResult]) -> Result<(), MapfmClientError> {
+        Ok(())
+    }
+
+    fn submit_batch(&self, _: &BenchmarkDescriptor, _: i64, _: &[(usize, SolveResult)]) -> Result<(), MapfmClientError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::{MapfmClientError, ProgressiveDescriptor};
+    use crate::generator::ProgressiveGenerator;
+    use crate::grid::Grid;
+    use crate::marked::MarkedCoordinate;
+    use std::collections::{BTreeMap, HashSet};
+
+    #[test]
+    pub fn test_generate() {
+        let grid = Grid::from_int_vecs(4, 3, vec![vec![0, 0, 0, 0], vec![0, 1, 1, 0], vec![0, 0, 0, 0]]);
+        let descriptor = ProgressiveDescriptor::new(8, 3, 3, 1);
+        let generator = ProgressiveGenerator::new(grid.clone(), 7).with_instances_per_size(2);
+
+        let problems = generator.generate(&descriptor).unwrap();
+        assert_eq!(problems, generator.generate(&descriptor).unwrap());
+        assert_ne!(problems, ProgressiveGenerator::new(grid.clone(), 8).with_instances_per_size(2).generate(&descriptor).unwrap());
+        assert_eq!(problems.iter().map(|i| i.starts.len()).collect::<Vec<_>>(), vec![3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8]);
+
+        for problem in &problems {
+            for placed in [&problem.starts, &problem.goals] {
+                assert_eq!(placed.iter().map(|i| **i).collect::<HashSet<_>>().len(), placed.len());
+                assert!(placed.iter().all(|i| grid.wall_at(**i) == Some(false)));
+            }
+
+            let teams = |placed: &[MarkedCoordinate]| {
+                let mut teams = BTreeMap::new();
+                for i in placed {
+                    *teams.entry(i.colour()).or_insert(0) += 1;
+                }
+                teams
+            };
+            let starts = teams(&problem.starts);
+            assert_eq!(starts, teams(&problem.goals));
+            assert!(starts.keys().all(|&i| (0..3).contains(&i)));
+            assert!(starts.values().max().unwrap() - starts.values().min().unwrap() <= 1);
+        }
+
+        assert!(matches!(generator.generate(&ProgressiveDescriptor::new(11, 1, 1, 0)), Err(MapfmClientError::Generator(_))));
+        assert!(matches!(generator.generate(&ProgressiveDescriptor::new(2, 3, 1, 0)), Err(MapfmClientError::Generator(_))));
+    }
+}
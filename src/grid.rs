@@ -16,12 +16,21 @@ impl Grid {
             height,
             obstacles: i.into_iter()
                 .map(|r| r.into_iter()
-                    .map(|b| if b == 0 { false } else { true } )
+                    .map(|b| b != 0)
                     .collect()
                 )
                 .collect(),
         }
     }
+
+    pub(crate) fn to_int_vecs(&self) -> Vec<Vec<i64>> {
+        self.obstacles.iter()
+            .map(|r| r.iter()
+                .map(|&b| b as i64)
+                .collect()
+            )
+            .collect()
+    }
 }
 
 impl Grid {
@@ -34,7 +43,7 @@ impl Grid {
 
     pub fn wall_at(&self, at: Coordinate) -> Option<bool> {
         if at.x < 0 || at.x >= self.width as i64 || at.y < 0 || at.y >= self.height as i64 {
-            return None
+            None
         } else {
             Some(self.obstacles[at.y as usize][at.x as usize])
         }
@@ -0,0 +1,607 @@
+use crate::client::{BenchmarkDescriptor, MapfmClientError, ProgressiveDescriptor, SerializedProblemList};
+use crate::problem::Problem;
+use crate::marked::MarkedCoordinate;
+use crate::coordinate::Coordinate;
+use crate::retry::RetryPolicy;
+use crate::cache::BenchmarkCache;
+use crate::catalog::BenchmarkInfo;
+use crate::results::{AttemptInfo, LeaderboardEntry, ProblemResult};
+use crate::cassette::{headers, RecordedRequest, RecordedResponse, Recorder, Replayer, TOKEN_HEADER};
+use crate::runner::SolveResult;
+use crate::token::Token;
+use crate::builder::DEFAULT_BASE_URL;
+use crate::http_config::{ClientSettings, HttpConfig};
+use crate::transport::{BenchmarkRequest, ProblemSource, SolutionSink};
+use reqwest::blocking::{Client, Request, RequestBuilder};
+use reqwest::{Client as AsyncClient, Request as AsyncRequest, RequestBuilder as AsyncRequestBuilder};
+use reqwest::header::HeaderMap;
+use reqwest::{Method, Url};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::OnceLock;
+
+enum Cassette {
+    Record(Recorder),
+    Replay(Replayer),
+}
+
+/// Talks to the mapf.nl server (or another server with the same api) over http.
+pub struct HttpTransport {
+    token: Token,
+    base_url: String,
+    retry_policy: RetryPolicy,
+    cache: Option<BenchmarkCache>,
+    cassette: Option<Cassette>,
+
+    /// Created on first use (from `settings`): constructing a blocking client inside an
+    /// async runtime panics, which would make it impossible to use the async api.
+    client: OnceLock<Client>,
+    settings: ClientSettings,
+    async_client: AsyncClient,
+}
+
+fn record_request(method: &Method, url: &Url, headers_map: &HeaderMap, body: Option<&[u8]>) -> RecordedRequest {
+    RecordedRequest {
+        method: method.to_string(),
+        path: url.path().to_string(),
+        headers: headers(headers_map.iter().map(|(name, value)| (name.as_str(), value.as_bytes()))),
+        body: String::from_utf8_lossy(body.unwrap_or_default()).into_owned(),
+    }
+}
+
+fn record_response(status: u16, headers_map: &HeaderMap, body: String) -> RecordedResponse {
+    RecordedResponse {
+        status,
+        headers: headers(headers_map.iter().map(|(name, value)| (name.as_str(), value.as_bytes()))),
+        body,
+    }
+}
+
+/// A 404 from an endpoint for a single benchmark means there is no such benchmark.
+fn benchmark_not_found(error: MapfmClientError) -> MapfmClientError {
+    match error {
+        MapfmClientError::NotFound(message) => MapfmClientError::BenchmarkNotFound(message),
+        error => error,
+    }
+}
+
+fn decode<T: DeserializeOwned>(resp: &RecordedResponse) -> Result<T, MapfmClientError> {
+    serde_json::from_str(&resp.body).map_err(MapfmClientError::JsonDecodeError)
+}
+
+impl HttpTransport {
+    /// A transport authenticating with `token`, against `base_url` (https://mapf.nl/ by default).
+    /// See [`Token::resolve`] to load the token from the environment or a config file. An
+    /// empty token is no token at all: requests fail with [`MissingToken`](MapfmClientError::MissingToken)
+    /// instead of being sent.
+    pub fn new(token: impl Into<Token>, base_url: Option<&str>) -> Self {
+        Self {
+            token: token.into(),
+            base_url: base_url.map(|i| i.to_string()).unwrap_or(DEFAULT_BASE_URL.to_string()),
+            retry_policy: RetryPolicy::default(),
+            cache: None,
+            cassette: None,
+
+            client: OnceLock::new(),
+            settings: ClientSettings::default(),
+            async_client: AsyncClient::new(),
+        }
+    }
+
+    /// A transport which doesn't talk to a server at all, but answers every request with
+    /// the response recorded for it in the cassette at `path` (see
+    /// [`with_recording`](Self::with_recording)), so a past session can be reproduced offline.
+    /// Requests to other endpoints than the recorded ones fail.
+    pub fn replay(path: impl AsRef<Path>) -> Result<Self, MapfmClientError> {
+        let replayer = Replayer::load(path.as_ref()).map_err(MapfmClientError::Cassette)?;
+
+        Ok(Self {
+            cassette: Some(Cassette::Replay(replayer)),
+            ..Self::new("", None)
+        })
+    }
+
+    /// Sets the policy used to retry requests to the server which failed
+    /// with a transient error. Defaults to [`RetryPolicy::default`].
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Stores the problems fetched for a benchmark (when not making an attempt) in `cache`,
+    /// and reads them from there when the cache is preferred over the network.
+    pub fn with_cache(mut self, cache: BenchmarkCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Writes every request sent to the server and the response to it into a cassette
+    /// file at `path`, which can be replayed with [`replay`](Self::replay). Headers are
+    /// recorded too, except for the one carrying the token.
+    pub fn with_recording(mut self, path: impl AsRef<Path>) -> Self {
+        self.cassette = Some(Cassette::Record(Recorder::new(path.as_ref())));
+        self
+    }
+
+    fn cached_benchmark(&self, descriptor: &BenchmarkDescriptor) -> Option<Vec<Problem>> {
+        self.cache.as_ref()
+            .filter(|i| i.prefers_cache())
+            .and_then(|i| i.load(descriptor))
+            .map(|i| i.problems())
+    }
+
+    fn cache_benchmark(&self, descriptor: &BenchmarkDescriptor, problems: &SerializedProblemList) {
+        if let Some(cache) = &self.cache {
+            // the cache is only an optimization, so not being able to write it isn't an error
+            let _ = cache.store(descriptor, problems);
+        }
+    }
+
+    /// Configures the http clients used to talk to the server. Fails when
+    /// something in `config` is invalid, like the url of the proxy.
+    pub fn with_http_config(mut self, config: &HttpConfig) -> Result<Self, MapfmClientError> {
+        self.settings = ClientSettings::parse(config)?;
+        self.async_client = self.settings.async_client()?;
+        self.client = OnceLock::new();
+
+        Ok(self)
+    }
+
+    /// Sends blocking requests (made by [`run`](crate::MapfBenchmarker::run)) with
+    /// `client`, instead of a client created by this transport.
+    pub fn with_client(mut self, client: Client) -> Self {
+        self.client = OnceLock::from(client);
+        self
+    }
+
+    /// Sends async requests (made by [`run_async`](crate::MapfBenchmarker::run_async))
+    /// with `client`, instead of a client created by this transport.
+    pub fn with_async_client(mut self, client: AsyncClient) -> Self {
+        self.async_client = client;
+        self
+    }
+
+    fn client(&self) -> Result<&Client, MapfmClientError> {
+        if let Some(client) = self.client.get() {
+            return Ok(client);
+        }
+
+        let client = self.settings.blocking_client()?;
+        Ok(self.client.get_or_init(|| client))
+    }
+
+    fn url(&self, path: &str) -> Result<Url, MapfmClientError> {
+        Url::parse(&self.base_url)
+            .map_err(|i| MapfmClientError::UrlParse(Box::new(i)))?
+            .join(path)
+            .map_err(|i| MapfmClientError::UrlParse(Box::new(i)))
+    }
+
+    fn benchmark_url(&self, descriptor: &BenchmarkDescriptor) -> Result<Url, MapfmClientError> {
+        self.url(&format!("/api/benchmark/attempt/{}", descriptor.identifier()))
+    }
+
+    fn submit_url(&self, attempt_id: i64) -> Result<Url, MapfmClientError> {
+        self.url(&format!("/api/solutions/submit/{}", attempt_id))
+    }
+
+    /// Turns a non-200 response into an error, keeping the token out of its message.
+    fn check_status(&self, resp: RecordedResponse) -> Result<RecordedResponse, MapfmClientError> {
+        if resp.status != 200 {
+            return Err(MapfmClientError::from_status(resp.status, self.token.redact(resp.body)))
+        }
+
+        Ok(resp)
+    }
+
+    fn replaying(&self) -> bool {
+        matches!(self.cassette, Some(Cassette::Replay(_)))
+    }
+
+    /// Takes the response to `request` from the cassette when replaying one.
+    fn replayed(&self, request: &RecordedRequest) -> Option<Result<RecordedResponse, MapfmClientError>> {
+        match &self.cassette {
+            Some(Cassette::Replay(replayer)) => Some(replayer.replay(request).map_err(MapfmClientError::Cassette)),
+            _ => None,
+        }
+    }
+
+    fn record(&self, request: RecordedRequest, response: &RecordedResponse) -> Result<(), MapfmClientError> {
+        match &self.cassette {
+            Some(Cassette::Record(recorder)) => recorder.record(request, response.clone()).map_err(MapfmClientError::Cassette),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_token(&self) -> Result<(), MapfmClientError> {
+        match self.token.expose() {
+            "" => Err(MapfmClientError::MissingToken),
+            _ => Ok(()),
+        }
+    }
+
+    fn exchange(&self, request: Request) -> Result<RecordedResponse, MapfmClientError> {
+        let recorded = record_request(request.method(), request.url(), request.headers(), request.body().and_then(|i| i.as_bytes()));
+        if let Some(response) = self.replayed(&recorded) {
+            return response;
+        }
+
+        self.check_token()?;
+        let resp = self.client()?.execute(request).map_err(MapfmClientError::RequestError)?;
+        let status = resp.status().as_u16();
+        let headers = resp.headers().clone();
+        let response = record_response(status, &headers, resp.text().map_err(MapfmClientError::RequestError)?);
+
+        self.record(recorded, &response)?;
+        Ok(response)
+    }
+
+    async fn exchange_async(&self, request: AsyncRequest) -> Result<RecordedResponse, MapfmClientError> {
+        let recorded = record_request(request.method(), request.url(), request.headers(), request.body().and_then(|i| i.as_bytes()));
+        if let Some(response) = self.replayed(&recorded) {
+            return response;
+        }
+
+        self.check_token()?;
+        let resp = self.async_client.execute(request).await.map_err(MapfmClientError::RequestError)?;
+        let status = resp.status().as_u16();
+        let headers = resp.headers().clone();
+        let response = record_response(status, &headers, resp.text().await.map_err(MapfmClientError::RequestError)?);
+
+        self.record(recorded, &response)?;
+        Ok(response)
+    }
+
+    /// Sends the request built by `request`, rebuilding and resending it according
+    /// to the retry policy. The body is serialized again from the same data on each
+    /// attempt, so nothing that went into it (like solutions) has to be recomputed.
+    /// Any non-200 response left after retrying is turned into an error. See
+    /// [`RetryPolicy`] for what isn't retried when the request isn't `idempotent`.
+    fn send(&self, request: impl Fn() -> RequestBuilder, idempotent: bool) -> Result<RecordedResponse, MapfmClientError> {
+        let mut attempt = 0;
+        loop {
+            let last = attempt + 1 >= self.retry_policy.max_attempts();
+
+            match self.exchange(request().build().map_err(MapfmClientError::RequestError)?) {
+                Ok(resp) if !last && self.retry_policy.retry_status(resp.status, idempotent) => {},
+                Err(MapfmClientError::RequestError(e)) if !last && self.retry_policy.retry_error(&e, idempotent) => {},
+                res => return self.check_status(res?),
+            }
+
+            // a replayed session has nothing to wait for
+            if !self.replaying() {
+                std::thread::sleep(self.retry_policy.backoff(attempt));
+            }
+            attempt += 1;
+        }
+    }
+
+    fn post(&self, url: Url, data: &impl Serialize, idempotent: bool) -> Result<RecordedResponse, MapfmClientError> {
+        let client = self.client()?;
+        self.send(|| client
+            .post(url.clone())
+            .header(TOKEN_HEADER, self.token.expose())
+            .json(data),
+            idempotent,
+        )
+    }
+
+    fn get(&self, url: Url) -> Result<RecordedResponse, MapfmClientError> {
+        let client = self.client()?;
+        self.send(|| client
+            .get(url.clone())
+            .header(TOKEN_HEADER, self.token.expose()),
+            true,
+        )
+    }
+
+    async fn send_async(&self, request: impl Fn() -> AsyncRequestBuilder, idempotent: bool) -> Result<RecordedResponse, MapfmClientError> {
+        let mut attempt = 0;
+        loop {
+            let last = attempt + 1 >= self.retry_policy.max_attempts();
+
+            match self.exchange_async(request().build().map_err(MapfmClientError::RequestError)?).await {
+                Ok(resp) if !last && self.retry_policy.retry_status(resp.status, idempotent) => {},
+                Err(MapfmClientError::RequestError(e)) if !last && self.retry_policy.retry_error(&e, idempotent) => {},
+                res => return self.check_status(res?),
+            }
+
+            if !self.replaying() {
+                tokio::time::sleep(self.retry_policy.backoff(attempt)).await;
+            }
+            attempt += 1;
+        }
+    }
+
+    async fn post_async(&self, url: Url, data: &impl Serialize, idempotent: bool) -> Result<RecordedResponse, MapfmClientError> {
+        self.send_async(|| self.async_client
+            .post(url.clone())
+            .header(TOKEN_HEADER, self.token.expose())
+            .json(data),
+            idempotent,
+        ).await
+    }
+
+    async fn get_async(&self, url: Url) -> Result<RecordedResponse, MapfmClientError> {
+        self.send_async(|| self.async_client
+            .get(url.clone())
+            .header(TOKEN_HEADER, self.token.expose()),
+            true,
+        ).await
+    }
+
+    /// Every benchmark available on the server, with its metadata.
+    ///
+    /// **Experimental:** unlike starting attempts and submitting solutions, this endpoint
+    /// (`/api/benchmark/list`) and the shape of [`BenchmarkInfo`] aren't confirmed against
+    /// the server's api. They are only tested against a local mock, so they may not
+    /// work with mapf.nl, and may change once the real api is known.
+    pub fn list_benchmarks(&self) -> Result<Vec<BenchmarkInfo>, MapfmClientError> {
+        decode(&self.get(self.url("/api/benchmark/list")?)?)
+    }
+
+    /// The metadata of the benchmark with `identifier`.
+    ///
+    /// **Experimental**, like [`list_benchmarks`](Self::list_benchmarks): the endpoint
+    /// (`/api/benchmark/info/{identifier}`) isn't confirmed against the server's api.
+    pub fn benchmark_info(&self, identifier: usize) -> Result<BenchmarkInfo, MapfmClientError> {
+        decode(&self.get(self.url(&format!("/api/benchmark/info/{}", identifier))?).map_err(benchmark_not_found)?)
+    }
+
+    /// Async version of [`list_benchmarks`](Self::list_benchmarks), and just as experimental.
+    pub async fn list_benchmarks_async(&self) -> Result<Vec<BenchmarkInfo>, MapfmClientError> {
+        decode(&self.get_async(self.url("/api/benchmark/list")?).await?)
+    }
+
+    /// Async version of [`benchmark_info`](Self::benchmark_info), and just as experimental.
+    pub async fn benchmark_info_async(&self, identifier: usize) -> Result<BenchmarkInfo, MapfmClientError> {
+        decode(&self.get_async(self.url(&format!("/api/benchmark/info/{}", identifier))?).await.map_err(benchmark_not_found)?)
+    }
+
+    fn attempts_url(&self, algorithm_name: &str, version: &str) -> Result<Url, MapfmClientError> {
+        let mut url = self.url("/api/attempts/list")?;
+        url.query_pairs_mut()
+            .append_pair("algorithm", algorithm_name)
+            .append_pair("version", version);
+
+        Ok(url)
+    }
+
+    /// The attempts made before with `version` of the algorithm called `algorithm_name`.
+    ///
+    /// **Experimental**, like [`list_benchmarks`](Self::list_benchmarks): the endpoints for
+    /// past attempts and leaderboards (`/api/attempts/list`, `/api/attempts/{id}/results` and
+    /// `/api/benchmark/leaderboard/{identifier}`) and the shapes of [`AttemptInfo`],
+    /// [`ProblemResult`] and [`LeaderboardEntry`] aren't confirmed against the server's api.
+    pub fn list_attempts(&self, algorithm_name: &str, version: &str) -> Result<Vec<AttemptInfo>, MapfmClientError> {
+        decode(&self.get(self.attempts_url(algorithm_name, version)?)?)
+    }
+
+    /// How the server judged the solution for every problem of an attempt.
+    /// **Experimental**, see [`list_attempts`](Self::list_attempts).
+    pub fn attempt_results(&self, attempt_id: i64) -> Result<Vec<ProblemResult>, MapfmClientError> {
+        decode(&self.get(self.url(&format!("/api/attempts/{}/results", attempt_id))?)?)
+    }
+
+    /// The leaderboard of the benchmark with `identifier`, best entry first.
+    /// **Experimental**, see [`list_attempts`](Self::list_attempts).
+    pub fn leaderboard(&self, identifier: usize) -> Result<Vec<LeaderboardEntry>, MapfmClientError> {
+        decode(&self.get(self.url(&format!("/api/benchmark/leaderboard/{}", identifier))?).map_err(benchmark_not_found)?)
+    }
+
+    /// Async version of [`list_attempts`](Self::list_attempts), and just as experimental.
+    pub async fn list_attempts_async(&self, algorithm_name: &str, version: &str) -> Result<Vec<AttemptInfo>, MapfmClientError> {
+        decode(&self.get_async(self.attempts_url(algorithm_name, version)?).await?)
+    }
+
+    /// Async version of [`attempt_results`](Self::attempt_results), and just as experimental.
+    pub async fn attempt_results_async(&self, attempt_id: i64) -> Result<Vec<ProblemResult>, MapfmClientError> {
+        decode(&self.get_async(self.url(&format!("/api/attempts/{}/results", attempt_id))?).await?)
+    }
+
+    /// Async version of [`leaderboard`](Self::leaderboard), and just as experimental.
+    pub async fn leaderboard_async(&self, identifier: usize) -> Result<Vec<LeaderboardEntry>, MapfmClientError> {
+        decode(&self.get_async(self.url(&format!("/api/benchmark/leaderboard/{}", identifier))?).await.map_err(benchmark_not_found)?)
+    }
+
+    pub(crate) async fn validate_async(&self, request: &BenchmarkRequest<'_>) -> Result<(), MapfmClientError> {
+        self.post_async(self.benchmark_url(request.descriptor)?, &GetBenchmarkData::new(request, false), true).await.map_err(benchmark_not_found)?;
+
+        Ok(())
+    }
+
+    pub(crate) async fn get_benchmark_async(&self, request: &BenchmarkRequest<'_>) -> Result<Vec<Problem>, MapfmClientError> {
+        if let Some(problems) = self.cached_benchmark(request.descriptor) {
+            return Ok(problems)
+        }
+
+        let resp = self.post_async(self.benchmark_url(request.descriptor)?, &GetBenchmarkData::new(request, false), true).await.map_err(benchmark_not_found)?;
+
+        let data: SerializedProblemList = decode(&resp)?;
+        self.cache_benchmark(request.descriptor, &data);
+
+        Ok(data.problems())
+    }
+
+    pub(crate) async fn start_attempt_async(&self, request: &BenchmarkRequest<'_>) -> Result<(Vec<Problem>, i64), MapfmClientError> {
+        let resp = self.post_async(self.benchmark_url(request.descriptor)?, &GetBenchmarkData::new(request, true), false).await.map_err(benchmark_not_found)?;
+
+        let data: SerializedProblemList = decode(&resp)?;
+
+        Ok((data.problems(), data.attempt_id))
+    }
+
+    pub(crate) async fn submit_solutions_async(&self, descriptor: &BenchmarkDescriptor, attempt_id: i64, solutions: &[SolveResult]) -> Result<(), MapfmClientError> {
+        self.post_async(self.submit_url(attempt_id)?, &SubmitSolutionData::new(descriptor, solutions), false).await?;
+
+        Ok(())
+    }
+
+    pub(crate) async fn submit_batch_async(&self, descriptor: &BenchmarkDescriptor, attempt_id: i64, batch: &[(usize, SolveResult)]) -> Result<(), MapfmClientError> {
+        self.post_async(self.submit_url(attempt_id)?, &SubmitSolutionData::batch(descriptor, batch), false).await?;
+
+        Ok(())
+    }
+}
+
+impl ProblemSource for HttpTransport {
+    /// Makes the same request as [`get_benchmark`](Self::get_benchmark), but never answers
+    /// it from the cache, so a token the server doesn't accept fails with
+    /// [`Unauthorized`](MapfmClientError::Unauthorized) before any work is started.
+    fn validate(&self, request: &BenchmarkRequest) -> Result<(), MapfmClientError> {
+        self.post(self.benchmark_url(request.descriptor)?, &GetBenchmarkData::new(request, false), true).map_err(benchmark_not_found)?;
+
+        Ok(())
+    }
+
+    fn get_benchmark(&self, request: &BenchmarkRequest) -> Result<Vec<Problem>, MapfmClientError> {
+        if let Some(problems) = self.cached_benchmark(request.descriptor) {
+            return Ok(problems)
+        }
+
+        let resp = self.post(self.benchmark_url(request.descriptor)?, &GetBenchmarkData::new(request, false), true).map_err(benchmark_not_found)?;
+
+        let data: SerializedProblemList = decode(&resp)?;
+        self.cache_benchmark(request.descriptor, &data);
+
+        Ok(data.problems())
+    }
+
+    fn start_attempt(&self, request: &BenchmarkRequest) -> Result<(Vec<Problem>, i64), MapfmClientError> {
+        let resp = self.post(self.benchmark_url(request.descriptor)?, &GetBenchmarkData::new(request, true), false).map_err(benchmark_not_found)?;
+
+        let data: SerializedProblemList = decode(&resp)?;
+
+        Ok((data.problems(), data.attempt_id))
+    }
+}
+
+impl SolutionSink for HttpTransport {
+    fn submit_solutions(&self, descriptor: &BenchmarkDescriptor, attempt_id: i64, solutions: &[SolveResult]) -> Result<(), MapfmClientError> {
+        self.post(self.submit_url(attempt_id)?, &SubmitSolutionData::new(descriptor, solutions), false)?;
+
+        Ok(())
+    }
+
+    /// **Experimental:** a batch is sent to the same endpoint as all solutions at once
+    /// (`/api/solutions/submit/{id}`), with the index of its problem in every solution. That
+    /// the server takes several such requests for one attempt isn't confirmed against its api.
+    fn submit_batch(&self, descriptor: &BenchmarkDescriptor, attempt_id: i64, batch: &[(usize, SolveResult)]) -> Result<(), MapfmClientError> {
+        self.post(self.submit_url(attempt_id)?, &SubmitSolutionData::batch(descriptor, batch), false)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct ProgressiveParams {
+    num_agents: usize,
+    num_teams: usize,
+    max_diff: usize,
+
+    starts: Vec<MarkedCoordinate>,
+    goals: Vec<MarkedCoordinate>,
+}
+
+#[derive(Serialize)]
+struct SerializedPath {
+    route: Vec<Coordinate>
+}
+
+#[derive(Serialize)]
+struct SerializedSolutionData {
+    paths: Vec<SerializedPath>
+}
+
+#[derive(Serialize)]
+struct SerializedSolution {
+    /// Index of the problem in the attempt. Only sent when solutions are submitted in batches.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    index: Option<usize>,
+    time: u128,
+    /// `None` for problems the solver didn't solve, for whatever reason. Sent as `null`,
+    /// which is experimental, see [`SolverOutcome`](crate::SolverOutcome).
+    solution: Option<SerializedSolutionData>,
+    progressive_params: Option<ProgressiveParams>
+}
+
+#[derive(Serialize)]
+struct SubmitSolutionData {
+    solutions: Vec<SerializedSolution>,
+    benchmark: usize,
+    progressive: bool,
+}
+
+impl SubmitSolutionData {
+    fn new(descriptor: &BenchmarkDescriptor, solutions: &[SolveResult]) -> Self {
+        Self {
+            solutions: solutions.iter().map(|(outcome, prob, time)| {
+                SerializedSolution {
+                    index: None,
+                    time: time.as_nanos(),
+                    solution: outcome.solution().map(|sol| SerializedSolutionData {
+                        paths: sol.paths.iter()
+                            .map(|i| SerializedPath {
+                                route: i.clone()
+                            })
+                            .collect()
+                    }),
+                    progressive_params: descriptor.progressive_descriptor().map(|i| {
+                        ProgressiveParams {
+                            num_agents: prob.starts.len(),
+                            num_teams: i.num_teams(),
+                            max_diff: i.max_diff(),
+                            starts: prob.starts.clone(),
+                            goals: prob.goals.clone()
+                        }
+                    })
+                }
+            }).collect(),
+            benchmark: descriptor.identifier(),
+            progressive: descriptor.progressive()
+        }
+    }
+
+    fn batch(descriptor: &BenchmarkDescriptor, batch: &[(usize, SolveResult)]) -> Self {
+        let mut data = Self::new(descriptor, &batch.iter().map(|(_, res)| res.clone()).collect::<Vec<_>>());
+        for (solution, (index, _)) in data.solutions.iter_mut().zip(batch) {
+            solution.index = Some(*index);
+        }
+
+        data
+    }
+}
+
+/// The exact body sent to the server when submitting `solutions` all at once.
+pub(crate) fn submission_body(descriptor: &BenchmarkDescriptor, solutions: &[SolveResult]) -> Vec<u8> {
+    serde_json::to_vec(&SubmitSolutionData::new(descriptor, solutions)).expect("solutions can always be serialized")
+}
+
+/// The exact body sent to the server when submitting a batch of solutions.
+pub(crate) fn batch_submission_body(descriptor: &BenchmarkDescriptor, batch: &[(usize, SolveResult)]) -> Vec<u8> {
+    serde_json::to_vec(&SubmitSolutionData::batch(descriptor, batch)).expect("solutions can always be serialized")
+}
+
+#[derive(Serialize)]
+struct GetBenchmarkData {
+    algorithm: String,
+    version: String,
+    debug: bool,
+    progressive: bool,
+    progressive_description: Option<ProgressiveDescriptor>,
+    create_attempt: bool,
+}
+
+impl GetBenchmarkData {
+    fn new(request: &BenchmarkRequest, attempt: bool) -> Self {
+        Self {
+            algorithm: request.algorithm_name.to_string(),
+            version: request.version.to_string(),
+            debug: request.debug,
+            progressive: request.descriptor.progressive(),
+            progressive_description: request.descriptor.progressive_descriptor().cloned(),
+            create_attempt: attempt,
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use crate::client::MapfmClientError;
+use reqwest::blocking::{Client, ClientBuilder};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Certificate, Client as AsyncClient, ClientBuilder as AsyncClientBuilder, Proxy};
+use std::time::Duration;
+
+/// Settings for the http clients an [`HttpTransport`](crate::HttpTransport) talks
+/// to the server with. Anything not set is left at reqwest's defaults.
+#[derive(Clone, Debug, Default)]
+pub struct HttpConfig {
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    proxy: Option<String>,
+    root_certificates: Vec<Vec<u8>>,
+    user_agent: Option<String>,
+    headers: Vec<(String, String)>,
+}
+
+impl HttpConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The time to wait for a connection to the server to be made.
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// The time to wait for a whole request, from connecting until the response
+    /// has been read. Large submissions may need more than the default.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sends all requests through the proxy at `url`.
+    pub fn with_proxy(mut self, url: &str) -> Self {
+        self.proxy = Some(url.to_string());
+        self
+    }
+
+    /// Trusts the (PEM encoded) certificate `pem` on top of the system's root certificates.
+    pub fn with_root_certificate(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.root_certificates.push(pem.into());
+        self
+    }
+
+    pub fn with_user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = Some(user_agent.to_string());
+        self
+    }
+
+    /// Sends the header `name` with `value` along with every request.
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+}
+
+fn invalid(error: impl std::error::Error + Send + Sync + 'static) -> MapfmClientError {
+    MapfmClientError::ClientConfig(Box::new(error))
+}
+
+/// An [`HttpConfig`] of which everything has been checked and parsed, to build clients from.
+#[derive(Clone, Default)]
+pub(crate) struct ClientSettings {
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    proxy: Option<Proxy>,
+    root_certificates: Vec<Certificate>,
+    user_agent: Option<String>,
+    headers: HeaderMap,
+}
+
+impl ClientSettings {
+    pub fn parse(config: &HttpConfig) -> Result<Self, MapfmClientError> {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &config.headers {
+            headers.insert(
+                HeaderName::from_bytes(name.as_bytes()).map_err(invalid)?,
+                HeaderValue::from_str(value).map_err(invalid)?,
+            );
+        }
+
+        Ok(Self {
+            connect_timeout: config.connect_timeout,
+            timeout: config.timeout,
+            proxy: config.proxy.as_deref().map(Proxy::all).transpose().map_err(invalid)?,
+            root_certificates: config.root_certificates.iter()
+                .map(|i| Certificate::from_pem(i))
+                .collect::<Result<_, _>>()
+                .map_err(invalid)?,
+            user_agent: config.user_agent.clone(),
+            headers,
+        })
+    }
+
+    pub fn blocking_client(&self) -> Result<Client, MapfmClientError> {
+        let mut builder = ClientBuilder::new()
+            .default_headers(self.headers.clone());
+        // unlike the async client, the blocking client has a default timeout which `None` would turn off
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(proxy.clone());
+        }
+        if let Some(user_agent) = &self.user_agent {
+            builder = builder.user_agent(user_agent);
+        }
+        for certificate in &self.root_certificates {
+            builder = builder.add_root_certificate(certificate.clone());
+        }
+
+        builder.build().map_err(invalid)
+    }
+
+    pub fn async_client(&self) -> Result<AsyncClient, MapfmClientError> {
+        let mut builder = AsyncClientBuilder::new()
+            .default_headers(self.headers.clone());
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(proxy.clone());
+        }
+        if let Some(user_agent) = &self.user_agent {
+            builder = builder.user_agent(user_agent);
+        }
+        for certificate in &self.root_certificates {
+            builder = builder.add_root_certificate(certificate.clone());
+        }
+
+        builder.build().map_err(invalid)
+    }
+}
@@ -5,12 +5,50 @@ mod solution;
 mod marked;
 mod grid;
 mod client;
+mod retry;
+mod runner;
+mod checkpoint;
+mod cache;
+mod local;
+mod transport;
+mod http;
+mod http_config;
+mod cassette;
+mod catalog;
+mod results;
+mod report;
+mod observer;
+mod token;
+mod builder;
+mod solver;
+mod generator;
+mod artifacts;
+#[cfg(any(test, feature = "test-support"))]
+pub mod mock;
+#[cfg(test)]
+mod testing;
 pub mod ffi;
 
 pub use client::MapfmClientError;
 pub use client::MapfBenchmarker;
+pub use builder::{MapfBenchmarkerBuilder, DEFAULT_BASE_URL};
 pub use client::BenchmarkDescriptor;
 pub use client::ProgressiveDescriptor;
+pub use client::ErrorPolicy;
+pub use retry::RetryPolicy;
+pub use cache::BenchmarkCache;
+pub use transport::{Transport, ProblemSource, SolutionSink, BenchmarkRequest};
+pub use http::HttpTransport;
+pub use http_config::HttpConfig;
+pub use catalog::BenchmarkInfo;
+pub use results::{AttemptInfo, ProblemResult, LeaderboardEntry};
+pub use report::{RunReport, ProblemReport, ProblemStatus, BenchmarkOutcome};
+pub use observer::{Event, Observer};
+pub use token::{Token, TOKEN_ENV};
+pub use runner::SolveResult;
+pub use solver::{Solver, SolverOutcome};
+pub use generator::ProgressiveGenerator;
+pub use local::Offline;
 
 pub use grid::Grid;
 pub use marked::MarkedCoordinate;
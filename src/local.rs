@@ -0,0 +1,71 @@
+use crate::client::SerializedProblem;
+use crate::problem::Problem;
+use std::path::Path;
+use std::{fs, io};
+
+/// The transport of a benchmarker which only solves problems from disk, see
+/// [`MapfBenchmarkerBuilder::build_local`](crate::MapfBenchmarkerBuilder::build_local).
+/// It can't talk to a server, so only [`run_local`](crate::MapfBenchmarker::run_local)
+/// is available.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Offline;
+
+/// Reads every `.json` file in `dir` as a single problem, in the same format the
+/// server sends problems in. Problems are ordered by file name.
+pub(crate) fn load_problems(dir: &Path) -> io::Result<Vec<Problem>> {
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_file() && path.extension().is_some_and(|i| i == "json") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    paths.into_iter()
+        .map(|path| {
+            let problem: SerializedProblem = serde_json::from_slice(&fs::read(&path)?)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", path.display(), e)))?;
+
+            Ok(problem.to_problem())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::MapfBenchmarker;
+    use crate::local::load_problems;
+    use crate::problem::Problem;
+    use crate::solution::Solution;
+    use crate::testing::TempPath;
+    use std::fs;
+
+    #[test]
+    pub fn test_load_problems() {
+        let dir = TempPath::new("local");
+        fs::create_dir_all(&dir).unwrap();
+
+        let problem = |width: usize| format!(
+            r#"{{"width": {}, "height": 1, "grid": [{:?}], "starts": [], "goals": []}}"#,
+            width, vec![0; width]
+        );
+        fs::write(dir.join("b.json"), problem(2)).unwrap();
+        fs::write(dir.join("a.json"), problem(1)).unwrap();
+        fs::write(dir.join("notes.txt"), "not a problem").unwrap();
+
+        let problems = load_problems(&dir).unwrap();
+        assert_eq!(problems.len(), 2);
+        assert_eq!(problems[0].grid.width(), 1);
+        assert_eq!(problems[1].grid.width(), 2);
+
+        let report = MapfBenchmarker::builder("test", |_: Problem| Solution { paths: vec![] })
+            .build_local()
+            .run_local(&dir)
+            .unwrap();
+        assert_eq!((&report.descriptor, report.problems.len(), report.solved()), (&None, 2, 2));
+
+        fs::write(dir.join("c.json"), "{}").unwrap();
+        assert!(load_problems(&dir).is_err());
+    }
+}
@@ -12,6 +12,16 @@ pub struct MarkedCoordinate {
     colour: i64,
 }
 
+impl MarkedCoordinate {
+    pub(crate) fn new(coord: Coordinate, colour: i64) -> Self {
+        Self { coord, colour }
+    }
+
+    pub fn colour(&self) -> i64 {
+        self.colour
+    }
+}
+
 impl Deref for MarkedCoordinate {
     type Target = Coordinate;
 
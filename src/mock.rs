@@ -0,0 +1,332 @@
+use crate::client::SerializedProblem;
+use crate::problem::Problem;
+use reqwest::Url;
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// A request the mock server received, with its json body.
+#[derive(Clone, Debug)]
+pub struct Recorded {
+    /// The id in the path of the request: of the benchmark, or of the attempt submitted to.
+    pub id: i64,
+    pub token: Option<String>,
+    /// Every header of the request, by lowercase name.
+    pub headers: HashMap<String, String>,
+    pub body: Value,
+}
+
+#[derive(Default)]
+struct State {
+    token: Option<String>,
+    benchmarks: HashMap<usize, Vec<Problem>>,
+    next_attempt_id: i64,
+    /// Every failure queued up, with the start of the paths it is for.
+    failures: VecDeque<(Option<String>, u16, String)>,
+    delay: Option<Duration>,
+
+    benchmark_requests: Vec<Recorded>,
+    submissions: Vec<Recorded>,
+    /// Every attempt started, with the benchmark it is at and the request which started it.
+    attempts: Vec<(i64, usize, Value)>,
+}
+
+/// A local stand-in for the mapf.nl server, so tests don't need a network or a real token.
+/// Serves `/api/benchmark/attempt/{id}` and `/api/solutions/submit/{id}` on a local port
+/// until dropped. Benchmarks it doesn't know are answered with a 404.
+///
+/// Outside this crate's own tests, it is only available with the `test-support` feature:
+///
+/// ```ignore
+/// let server = MockServer::start().with_benchmark(1, problems);
+/// let benchmarker = MapfBenchmarker::builder("test", solver)
+///     .with_token("any-token")
+///     .with_benchmark(BenchmarkDescriptor::from_identifier(1))
+///     .with_base_url(&server.url())
+///     .build()?;
+/// ```
+pub struct MockServer {
+    addr: SocketAddr,
+    state: Arc<Mutex<State>>,
+    stopped: Arc<AtomicBool>,
+}
+
+impl MockServer {
+    /// Starts serving on a free local port, without any benchmarks.
+    pub fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let state = Arc::new(Mutex::new(State {
+            next_attempt_id: 1,
+            ..State::default()
+        }));
+        let stopped = Arc::new(AtomicBool::new(false));
+
+        let server_state = state.clone();
+        let server_stopped = stopped.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                if server_stopped.load(Ordering::SeqCst) {
+                    break;
+                }
+
+                if let Ok(stream) = stream {
+                    let state = server_state.clone();
+                    std::thread::spawn(move || handle(stream, &state));
+                }
+            }
+        });
+
+        Self {
+            addr,
+            state,
+            stopped,
+        }
+    }
+
+    /// Serves `problems` for the benchmark with `identifier`.
+    pub fn with_benchmark(self, identifier: usize, problems: Vec<Problem>) -> Self {
+        self.state.lock().unwrap().benchmarks.insert(identifier, problems);
+        self
+    }
+
+    /// The base url to point a benchmarker at.
+    pub fn url(&self) -> String {
+        format!("http://{}/", self.addr)
+    }
+
+    /// Only accepts requests with `token`. By default, any token is accepted.
+    pub fn with_token(self, token: &str) -> Self {
+        self.state.lock().unwrap().token = Some(token.to_string());
+        self
+    }
+
+    /// Answers the next request (whichever endpoint it is for) with `status` and `body`
+    /// instead of handling it. Calling this multiple times queues up multiple failures.
+    pub fn fail_next(&self, status: u16, body: &str) {
+        self.state.lock().unwrap().failures.push_back((None, status, body.to_string()));
+    }
+
+    /// Like [`fail_next`](Self::fail_next), but for the next request to a path starting
+    /// with `path`, so other requests are handled as usual.
+    pub fn fail_next_at(&self, path: &str, status: u16, body: &str) {
+        self.state.lock().unwrap().failures.push_back((Some(path.to_string()), status, body.to_string()));
+    }
+
+    /// Waits `delay` before answering every following request. Requests are still
+    /// handled right away, like a server which is slow to respond.
+    pub fn set_delay(&self, delay: Duration) {
+        self.state.lock().unwrap().delay = Some(delay);
+    }
+
+    /// Every request for the problems of a benchmark, with the id of the benchmark.
+    pub fn benchmark_requests(&self) -> Vec<Recorded> {
+        self.state.lock().unwrap().benchmark_requests.clone()
+    }
+
+    /// Every accepted submission, with the id of the attempt it was for.
+    pub fn submissions(&self) -> Vec<Recorded> {
+        self.state.lock().unwrap().submissions.clone()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        // wake up the accept loop so it sees it has to stop
+        let _ = TcpStream::connect(self.addr);
+    }
+}
+
+fn handle(stream: TcpStream, state: &Mutex<State>) {
+    let mut reader = BufReader::new(&stream);
+
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line).is_err() {
+        return;
+    }
+    let path = request_line.split_whitespace().nth(1).unwrap_or("").to_string();
+
+    let mut headers = HashMap::new();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).is_err() || line.trim().is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+        }
+    }
+    let content_length = headers.get("content-length").and_then(|i| i.parse().ok()).unwrap_or(0);
+
+    let mut body = vec![0; content_length];
+    if reader.read_exact(&mut body).is_err() {
+        return;
+    }
+    let body = serde_json::from_slice(&body).unwrap_or(Value::Null);
+
+    let (status, response) = respond(state, &path, headers, body);
+
+    let delay = state.lock().unwrap().delay;
+    if let Some(delay) = delay {
+        std::thread::sleep(delay);
+    }
+    let _ = write!(
+        &stream,
+        "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status, response.len(), response
+    );
+}
+
+/// The metadata of a benchmark, taken from its first problem. Mirrors [`BenchmarkInfo`](crate::BenchmarkInfo),
+/// not a captured response of the server, so it only tests the client against itself.
+fn info(id: usize, problems: &[Problem]) -> Value {
+    let first = problems.first();
+    let teams: HashSet<_> = first.iter().flat_map(|i| i.starts.iter().map(|i| i.colour())).collect();
+
+    json!({
+        "id": id,
+        "map_name": format!("map-{}", id),
+        "width": first.map_or(0, |i| i.grid.width()),
+        "height": first.map_or(0, |i| i.grid.height()),
+        "num_agents": first.map_or(0, |i| i.starts.len()),
+        "num_teams": teams.len(),
+        "num_problems": problems.len(),
+    })
+}
+
+/// Judges the submissions for an attempt like the server would (although more leniently):
+/// every submitted solution is valid, and costs the total length of its paths. Like the
+/// leaderboard, the shape of the results mirrors [`ProblemResult`](crate::ProblemResult)
+/// rather than a captured response of the server.
+fn results(state: &State, attempt_id: i64) -> Vec<Value> {
+    let mut results: Vec<_> = state.submissions.iter()
+        .filter(|i| i.id == attempt_id)
+        .flat_map(|i| i.body["solutions"].as_array().cloned().unwrap_or_default().into_iter().enumerate())
+        .map(|(position, solution)| {
+            let paths = solution["solution"]["paths"].as_array();
+            json!({
+                "index": solution["index"].as_u64().unwrap_or(position as u64),
+                "valid": paths.is_some(),
+                "cost": paths.map(|i| i.iter().map(|i| i["route"].as_array().map_or(0, |i| i.len())).sum::<usize>()),
+                "time_ns": solution["time"],
+                "error": if paths.is_some() { Value::Null } else { json!("no solution") },
+            })
+        })
+        .collect();
+    results.sort_by_key(|i| i["index"].as_u64());
+
+    results
+}
+
+fn leaderboard(state: &State, benchmark: usize) -> Vec<Value> {
+    let mut entries: Vec<_> = state.attempts.iter()
+        .filter(|(_, attempt_benchmark, _)| *attempt_benchmark == benchmark)
+        .map(|(id, _, request)| {
+            let results = results(state, *id);
+            let valid = results.iter().filter(|i| i["valid"] == true);
+
+            json!({
+                "user": "mock",
+                "algorithm": request["algorithm"],
+                "version": request["version"],
+                "attempt": id,
+                "solved": valid.clone().count(),
+                "total_cost": valid.clone().filter_map(|i| i["cost"].as_u64()).sum::<u64>(),
+                "total_time_ns": results.iter().filter_map(|i| i["time_ns"].as_u64()).sum::<u64>(),
+            })
+        })
+        .collect();
+    entries.sort_by_key(|i| (std::cmp::Reverse(i["solved"].as_u64()), i["total_cost"].as_u64()));
+
+    for (rank, entry) in entries.iter_mut().enumerate() {
+        entry["rank"] = json!(rank + 1);
+    }
+
+    entries
+}
+
+fn respond(state: &Mutex<State>, target: &str, headers: HashMap<String, String>, body: Value) -> (u16, String) {
+    let mut state = state.lock().unwrap();
+    let token = headers.get("x-api-token").cloned();
+    let failure = state.failures.iter().position(|(path, _, _)| path.as_ref().map_or(true, |i| target.starts_with(i.as_str())));
+    if let Some((_, status, body)) = failure.and_then(|i| state.failures.remove(i)) {
+        return (status, body);
+    }
+
+    if state.token.is_some() && state.token != token {
+        return (401, json!({"message": "invalid token"}).to_string());
+    }
+
+    let url = Url::parse(&format!("http://mock{}", target)).unwrap();
+    let query: HashMap<_, _> = url.query_pairs().into_owned().collect();
+    let path = url.path();
+
+    let id = |prefix: &str| path.strip_prefix(prefix).and_then(|i| i.parse::<i64>().ok());
+    let attempt_results = path.strip_prefix("/api/attempts/")
+        .and_then(|i| i.strip_suffix("/results"))
+        .and_then(|i| i.parse::<i64>().ok());
+
+    if path == "/api/attempts/list" {
+        let attempts: Vec<_> = state.attempts.iter()
+            .filter(|(_, _, request)| {
+                query.get("algorithm").is_some_and(|i| request["algorithm"] == **i)
+                    && query.get("version").is_some_and(|i| request["version"] == **i)
+            })
+            .map(|(id, benchmark, request)| json!({
+                "id": id,
+                "benchmark": benchmark,
+                "algorithm": request["algorithm"],
+                "version": request["version"],
+                "debug": request["debug"],
+                "progressive": request["progressive"],
+            }))
+            .collect();
+
+        (200, Value::Array(attempts).to_string())
+    } else if let Some(id) = attempt_results {
+        if !state.attempts.iter().any(|(attempt, _, _)| *attempt == id) {
+            return (404, json!({"message": format!("no attempt with id {}", id)}).to_string());
+        }
+
+        (200, Value::Array(results(&state, id)).to_string())
+    } else if let Some(id) = id("/api/benchmark/leaderboard/") {
+        (200, Value::Array(leaderboard(&state, id as usize)).to_string())
+    } else if path == "/api/benchmark/list" {
+        let mut ids: Vec<_> = state.benchmarks.keys().copied().collect();
+        ids.sort_unstable();
+
+        (200, Value::Array(ids.into_iter().map(|id| info(id, &state.benchmarks[&id])).collect()).to_string())
+    } else if let Some(id) = id("/api/benchmark/info/") {
+        match state.benchmarks.get(&(id as usize)) {
+            Some(problems) => (200, info(id as usize, problems).to_string()),
+            None => (404, json!({"message": format!("no benchmark with id {}", id)}).to_string()),
+        }
+    } else if let Some(id) = id("/api/benchmark/attempt/") {
+        state.benchmark_requests.push(Recorded { id, token, headers, body: body.clone() });
+
+        let problems = match state.benchmarks.get(&(id as usize)) {
+            Some(problems) => problems.iter().map(SerializedProblem::from_problem).collect::<Vec<_>>(),
+            None => return (404, json!({"message": format!("no benchmark with id {}", id)}).to_string()),
+        };
+
+        let attempt_id = state.next_attempt_id;
+        state.next_attempt_id += 1;
+        if body["create_attempt"] == true {
+            state.attempts.push((attempt_id, id as usize, body));
+        }
+
+        (200, json!({"benchmarks": problems, "attempt_id": attempt_id}).to_string())
+    } else if let Some(id) = id("/api/solutions/submit/") {
+        state.submissions.push(Recorded { id, token, headers, body });
+
+        (200, "{}".to_string())
+    } else {
+        (404, json!({"message": "not found"}).to_string())
+    }
+}
@@ -0,0 +1,64 @@
+use crate::client::{BenchmarkDescriptor, MapfmClientError};
+use crate::report::RunReport;
+use crate::runner::SolveResult;
+use crate::solver::SolverOutcome;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Something that happened while running benchmarks. Benchmarks are run one after another,
+/// so every event between an [`AttemptStarted`](Event::AttemptStarted) and the next
+/// [`BenchmarkFinished`](Event::BenchmarkFinished) or [`BenchmarkFailed`](Event::BenchmarkFailed)
+/// is about the same benchmark.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Event<'a> {
+    /// The problems of a benchmark were received and are about to be solved.
+    /// `attempt_id` is `None` when running without making an attempt.
+    AttemptStarted {
+        descriptor: &'a BenchmarkDescriptor,
+        attempt_id: Option<i64>,
+        num_problems: usize,
+    },
+    /// The solver was given the problem with `index`.
+    ProblemStarted { index: usize },
+    ProblemSolved { index: usize, duration: Duration },
+    /// The solver didn't solve the problem with `index`; `outcome` says why.
+    ProblemFailed { index: usize, duration: Duration, outcome: &'a SolverOutcome },
+    /// Solutions for the problems with `indices` are being sent to the server.
+    SubmissionSent { attempt_id: i64, indices: &'a [usize] },
+    /// The server accepted the solutions for the problems with `indices`.
+    SubmissionAcknowledged { attempt_id: i64, indices: &'a [usize] },
+    SubmissionFailed { attempt_id: i64, indices: &'a [usize], error: &'a MapfmClientError },
+    /// All problems of a benchmark were solved (and submitted, when making an attempt).
+    BenchmarkFinished { report: &'a RunReport },
+    /// Running a benchmark failed, so it won't be finished.
+    BenchmarkFailed { descriptor: &'a BenchmarkDescriptor, error: &'a MapfmClientError },
+}
+
+/// Receives the [`Event`]s of a [`MapfBenchmarker`](crate::MapfBenchmarker), for example
+/// to show progress. Events about problems are sent from the threads solving them.
+pub trait Observer: Send + Sync {
+    fn on_event(&self, event: &Event);
+}
+
+impl<F: Fn(&Event) + Send + Sync> Observer for F {
+    fn on_event(&self, event: &Event) {
+        self(event)
+    }
+}
+
+pub(crate) type Observers = Vec<Arc<dyn Observer>>;
+
+pub(crate) fn notify(observers: &[Arc<dyn Observer>], event: Event) {
+    for observer in observers {
+        observer.on_event(&event);
+    }
+}
+
+/// The event for a solved (or not solved) problem.
+pub(crate) fn problem_event(index: usize, (outcome, _, duration): &SolveResult) -> Event<'_> {
+    match outcome {
+        SolverOutcome::Solved(_) => Event::ProblemSolved { index, duration: *duration },
+        outcome => Event::ProblemFailed { index, duration: *duration, outcome },
+    }
+}
@@ -0,0 +1,146 @@
+use crate::client::{BenchmarkDescriptor, MapfmClientError};
+use crate::runner::SolveResult;
+use crate::solver::SolverOutcome;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ProblemStatus {
+    Solved,
+    /// The solver found that the problem has no solution.
+    Unsolvable,
+    /// The solver gave up, or didn't return within the time limit.
+    TimedOut,
+    /// The solver failed, see [`ProblemReport::error`].
+    Errored,
+    /// The problem wasn't given to the solver, because it kept failing on problems with fewer agents.
+    Skipped,
+}
+
+/// What happened to a single problem of a benchmark during a run.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ProblemReport {
+    /// The index of the problem in the benchmark.
+    pub index: usize,
+    pub status: ProblemStatus,
+    pub duration: Duration,
+    pub num_agents: usize,
+    /// The number of moves of the longest path in the solution, if there is one.
+    pub makespan: Option<usize>,
+    /// The number of moves of all paths in the solution together, if there is one.
+    pub sum_of_costs: Option<usize>,
+    /// Why the solver failed, if it did.
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+impl ProblemReport {
+    fn new(index: usize, (outcome, problem, duration): &SolveResult) -> Self {
+        let moves = outcome.solution().map(|i| i.paths.iter().map(|i| i.len().saturating_sub(1)).collect::<Vec<_>>());
+
+        Self {
+            index,
+            status: match outcome {
+                SolverOutcome::Solved(_) => ProblemStatus::Solved,
+                SolverOutcome::Unsolvable => ProblemStatus::Unsolvable,
+                SolverOutcome::TimedOut => ProblemStatus::TimedOut,
+                SolverOutcome::Error(_) => ProblemStatus::Errored,
+                SolverOutcome::Skipped => ProblemStatus::Skipped,
+            },
+            duration: *duration,
+            num_agents: problem.starts.len(),
+            makespan: moves.as_ref().map(|i| i.iter().copied().max().unwrap_or(0)),
+            sum_of_costs: moves.as_ref().map(|i| i.iter().sum()),
+            error: match outcome {
+                SolverOutcome::Error(error) => Some(error.clone()),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// The outcome of running a single benchmark, as computed locally.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct RunReport {
+    /// The benchmark which was run, or `None` for problems from a local directory (see
+    /// [`MapfBenchmarker::run_local`](crate::MapfBenchmarker::run_local)).
+    pub descriptor: Option<BenchmarkDescriptor>,
+    /// The attempt the solutions were submitted to, if one was made.
+    pub attempt_id: Option<i64>,
+    pub problems: Vec<ProblemReport>,
+}
+
+impl RunReport {
+    pub(crate) fn new(descriptor: Option<&BenchmarkDescriptor>, attempt_id: Option<i64>, results: &[SolveResult]) -> Self {
+        Self {
+            descriptor: descriptor.cloned(),
+            attempt_id,
+            problems: results.iter().enumerate().map(|(index, res)| ProblemReport::new(index, res)).collect(),
+        }
+    }
+
+    /// The number of problems which were solved.
+    pub fn solved(&self) -> usize {
+        self.problems.iter().filter(|i| i.status == ProblemStatus::Solved).count()
+    }
+
+    /// The time spent solving all problems together.
+    pub fn total_duration(&self) -> Duration {
+        self.problems.iter().map(|i| i.duration).sum()
+    }
+
+    /// The sum of costs of all solved problems together.
+    pub fn sum_of_costs(&self) -> usize {
+        self.problems.iter().filter_map(|i| i.sum_of_costs).sum()
+    }
+}
+
+/// What came of running a single benchmark: a report of the run, or why it failed.
+#[derive(Debug)]
+pub struct BenchmarkOutcome {
+    pub descriptor: BenchmarkDescriptor,
+    pub result: Result<RunReport, MapfmClientError>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::BenchmarkDescriptor;
+    use crate::coordinate::Coordinate;
+    use crate::grid::Grid;
+    use crate::problem::Problem;
+    use crate::report::{ProblemStatus, RunReport};
+    use crate::solution::Solution;
+    use crate::solver::SolverOutcome;
+    use std::time::Duration;
+
+    #[test]
+    pub fn test_report() {
+        let problem = Problem {
+            grid: Grid::from_int_vecs(3, 1, vec![vec![0, 0, 0]]),
+            starts: vec![],
+            goals: vec![],
+        };
+        let solution = Solution {
+            paths: vec![
+                vec![Coordinate::new(0, 0), Coordinate::new(1, 0), Coordinate::new(2, 0)],
+                vec![Coordinate::new(2, 0)],
+            ]
+        };
+
+        let report = RunReport::new(Some(&BenchmarkDescriptor::from_identifier(1)), Some(4), &[
+            (SolverOutcome::Solved(solution), problem.clone(), Duration::from_millis(2)),
+            (SolverOutcome::TimedOut, problem.clone(), Duration::from_millis(3)),
+            (SolverOutcome::Error("out of memory".to_string()), problem, Duration::from_millis(1)),
+        ]);
+
+        assert_eq!(report.problems[0].status, ProblemStatus::Solved);
+        assert_eq!(report.problems[0].makespan, Some(2));
+        assert_eq!(report.problems[1].status, ProblemStatus::TimedOut);
+        assert_eq!(report.problems[1].sum_of_costs, None);
+        assert_eq!(report.problems[2].status, ProblemStatus::Errored);
+        assert_eq!(report.problems[2].error.as_deref(), Some("out of memory"));
+        assert_eq!(report.solved(), 1);
+        assert_eq!(report.sum_of_costs(), 2);
+        assert_eq!(report.total_duration(), Duration::from_millis(6));
+    }
+}
@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// An attempt made at a benchmark before, as listed by
+/// [`HttpTransport::list_attempts`](crate::HttpTransport::list_attempts).
+///
+/// **Experimental:** like the other types about past attempts, these fields are what the
+/// client expects the server to send, which hasn't been checked against a real response yet.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct AttemptInfo {
+    pub id: i64,
+    /// The identifier of the benchmark the attempt was at.
+    pub benchmark: usize,
+    pub algorithm: String,
+    pub version: String,
+    pub debug: bool,
+    pub progressive: bool,
+}
+
+/// How the server judged the solution submitted for a single problem of an attempt.
+/// **Experimental**, see [`AttemptInfo`].
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ProblemResult {
+    /// The index of the problem in the attempt.
+    pub index: usize,
+    /// Whether a solution was submitted and the server found it valid.
+    pub valid: bool,
+    /// The cost of the solution, if it was valid.
+    pub cost: Option<u64>,
+    /// How long solving took, in nanoseconds.
+    pub time_ns: u64,
+    /// Why the solution was found invalid, if it was.
+    pub error: Option<String>,
+}
+
+impl ProblemResult {
+    pub fn time(&self) -> Duration {
+        Duration::from_nanos(self.time_ns)
+    }
+}
+
+/// A single row of the leaderboard of a benchmark. **Experimental**, see [`AttemptInfo`].
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct LeaderboardEntry {
+    pub rank: usize,
+    pub user: String,
+    pub algorithm: String,
+    pub version: String,
+    /// The id of the attempt this entry is for.
+    pub attempt: i64,
+    pub solved: usize,
+    pub total_cost: u64,
+    /// How long solving all problems took together, in nanoseconds.
+    pub total_time_ns: u64,
+}
+
+impl LeaderboardEntry {
+    pub fn total_time(&self) -> Duration {
+        Duration::from_nanos(self.total_time_ns)
+    }
+}
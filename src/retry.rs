@@ -0,0 +1,135 @@
+use std::time::Duration;
+use rand::Rng;
+
+/// The status codes telling a request wasn't handled by the server, so it can be sent again
+/// even when handling it twice would do harm.
+pub(crate) const NOT_HANDLED: [u16; 3] = [408, 429, 503];
+
+/// Decides how often, and how long apart, requests to the server are retried.
+///
+/// Only transport failures (timeouts, failed connections) and the configured status
+/// codes are retried; any other response is handed back to the caller untouched.
+/// Requests which must not reach the server twice, like starting an attempt or submitting
+/// solutions, aren't retried after a timeout and only retried on the configured codes which
+/// mean the server didn't handle them (408, 429 and 503): the server may have handled them
+/// already, and sending them again would start a second attempt or submit twice.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetryPolicy {
+    max_attempts: usize,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    multiplier: f64,
+    jitter: f64,
+    retryable_statuses: Vec<u16>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            multiplier: 2.0,
+            jitter: 0.5,
+            retryable_statuses: vec![408, 429, 500, 502, 503, 504],
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy which makes every request exactly once.
+    pub fn none() -> Self {
+        Self::new(1)
+    }
+
+    /// The default policy, making at most `max_attempts` attempts per request (including the first).
+    pub fn new(max_attempts: usize) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            ..Self::default()
+        }
+    }
+
+    /// The backoff before the first retry, and the upper bound any backoff is capped at.
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// The factor the backoff grows by after every failed attempt.
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier.max(1.0);
+        self
+    }
+
+    /// The fraction (between 0 and 1) of each backoff that is randomized, so
+    /// concurrent clients don't all retry at the same moment.
+    pub fn with_jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    /// The status codes for which a request is retried.
+    pub fn with_retryable_statuses(mut self, statuses: Vec<u16>) -> Self {
+        self.retryable_statuses = statuses;
+        self
+    }
+
+    pub fn max_attempts(&self) -> usize {
+        self.max_attempts
+    }
+
+    /// Whether to retry a request answered with `status`. A request which isn't
+    /// `idempotent` is only retried when the status says it wasn't handled.
+    pub(crate) fn retry_status(&self, status: u16, idempotent: bool) -> bool {
+        self.retryable_statuses.contains(&status) && (idempotent || NOT_HANDLED.contains(&status))
+    }
+
+    /// Whether to retry a request which failed with `error`. A request which couldn't
+    /// connect never reached the server, but one which timed out may have, so that is
+    /// only retried when the request is `idempotent`, and sending it twice does no harm.
+    pub(crate) fn retry_error(&self, error: &reqwest::Error, idempotent: bool) -> bool {
+        error.is_connect() || (idempotent && error.is_timeout())
+    }
+
+    /// How long to wait after the `attempt`th (zero based) attempt failed.
+    pub(crate) fn backoff(&self, attempt: usize) -> Duration {
+        let exponent = attempt.min(i32::MAX as usize) as i32;
+        let backoff = self.initial_backoff.as_secs_f64() * self.multiplier.powi(exponent);
+        let backoff = backoff.min(self.max_backoff.as_secs_f64());
+
+        let jitter = if self.jitter > 0.0 {
+            rand::thread_rng().gen_range(0.0..=self.jitter)
+        } else {
+            0.0
+        };
+
+        Duration::from_secs_f64(backoff * (1.0 - jitter))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::retry::RetryPolicy;
+    use std::time::Duration;
+
+    #[test]
+    pub fn test_backoff() {
+        let policy = RetryPolicy::new(10)
+            .with_backoff(Duration::from_millis(100), Duration::from_secs(1))
+            .with_jitter(0.0);
+
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(1), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(800));
+        assert_eq!(policy.backoff(4), Duration::from_secs(1));
+
+        let policy = policy.with_jitter(0.5);
+        for attempt in 0..10 {
+            let backoff = policy.backoff(attempt);
+            assert!(backoff <= Duration::from_secs(1));
+            assert!(backoff >= Duration::from_millis(50));
+        }
+    }
+}
@@ -0,0 +1,249 @@
+use crate::problem::Problem;
+use std::time::{Duration, Instant};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Mutex;
+use crate::solver::{SharedSolver, SolverOutcome};
+
+/// The outcome of solving a single problem: what the solver made of it (or
+/// [`TimedOut`](SolverOutcome::TimedOut) if it didn't return in time), the problem
+/// itself and how long solving took.
+pub type SolveResult = (SolverOutcome, Problem, Duration);
+
+/// Wall-clock budgets for solving. A problem which isn't solved within its budget
+/// is recorded as unsolved.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub(crate) struct TimeLimits {
+    pub problem: Option<Duration>,
+    pub benchmark: Option<Duration>,
+}
+
+impl TimeLimits {
+    /// The limit for the next problem, given how long the benchmark has been running.
+    fn next_problem(&self, elapsed: Duration) -> Option<Duration> {
+        let remaining = self.benchmark.map(|i| i.checked_sub(elapsed).unwrap_or_default());
+
+        match (self.problem, remaining) {
+            (Some(problem), Some(remaining)) => Some(problem.min(remaining)),
+            (problem, remaining) => problem.or(remaining),
+        }
+    }
+}
+
+/// Solves a single problem. When there is a time limit, the solver runs on its own
+/// thread so we can stop waiting for it. Threads can't be killed, so a solver which
+/// runs over its limit keeps running in the background until it returns by itself.
+fn solve_with_limit(solver: &SharedSolver, problem: Problem, limit: Option<Duration>) -> SolveResult {
+    let t1 = Instant::now();
+
+    let outcome = match limit {
+        None => solver.solve(problem.clone()),
+        Some(limit) if limit == Duration::default() => SolverOutcome::TimedOut,
+        Some(limit) => {
+            let (tx, rx) = mpsc::channel();
+            let p = problem.clone();
+            let solver = solver.clone();
+            let handle = std::thread::spawn(move || {
+                let _ = tx.send(solver.solve(p));
+            });
+
+            match rx.recv_timeout(limit) {
+                Ok(outcome) => outcome,
+                Err(RecvTimeoutError::Timeout) => SolverOutcome::TimedOut,
+                Err(RecvTimeoutError::Disconnected) => match handle.join() {
+                    Err(e) => std::panic::resume_unwind(e),
+                    Ok(()) => SolverOutcome::TimedOut,
+                }
+            }
+        }
+    };
+
+    let duration = Instant::now().duration_since(t1);
+    (outcome, problem, duration)
+}
+
+/// Keeps track of how often the solver failed in a row, and once that happened `cutoff`
+/// times, stops it from getting problems with more agents than the fewest it failed on
+/// in that run of failures.
+pub(crate) struct Escalation {
+    cutoff: usize,
+    state: Mutex<EscalationState>,
+}
+
+#[derive(Default)]
+struct EscalationState {
+    /// The number of problems the solver failed on since it last solved one.
+    failures: usize,
+    /// The fewest agents among those failures.
+    fewest_agents: Option<usize>,
+    /// The most agents the solver still gets problems with, once there is such a limit.
+    max_agents: Option<usize>,
+}
+
+impl Escalation {
+    pub fn new(cutoff: usize) -> Self {
+        Self {
+            cutoff: cutoff.max(1),
+            state: Mutex::new(EscalationState::default()),
+        }
+    }
+
+    fn skips(&self, problem: &Problem) -> bool {
+        let state = self.state.lock().unwrap();
+        state.max_agents.is_some_and(|i| problem.starts.len() > i)
+    }
+
+    /// Counts the result of a problem, in the order problems are solved in.
+    pub fn record(&self, (outcome, problem, _): &SolveResult) {
+        // a skipped problem says nothing about the solver
+        if *outcome == SolverOutcome::Skipped {
+            return;
+        }
+
+        let state = &mut *self.state.lock().unwrap();
+        if outcome.is_solved() {
+            state.failures = 0;
+            state.fewest_agents = None;
+            return;
+        }
+
+        let num_agents = problem.starts.len();
+        state.failures += 1;
+        state.fewest_agents = Some(state.fewest_agents.map_or(num_agents, |i| i.min(num_agents)));
+        if state.failures >= self.cutoff {
+            let fewest = state.fewest_agents.unwrap_or(num_agents);
+            state.max_agents = Some(state.max_agents.map_or(fewest, |i| i.min(fewest)));
+        }
+    }
+}
+
+/// Solves all problems, returning the results in the same order as the problems.
+/// With more than one worker, problems are solved concurrently on that many threads;
+/// each problem's duration is still measured on its own.
+///
+/// `on_start` is called with the index of every problem right before solving it, and
+/// `on_result` with the index and result of every problem as soon as it is solved,
+/// which (with multiple workers) is not necessarily in order.
+///
+/// With an `escalation`, problems with more agents than the solver kept failing on
+/// are skipped instead of solved.
+pub(crate) fn run_problems(
+    solver: &SharedSolver,
+    problems: Vec<Problem>,
+    limits: TimeLimits,
+    escalation: Option<Escalation>,
+    workers: usize,
+    on_start: &(dyn Fn(usize) + Sync),
+    on_result: &(dyn Fn(usize, &SolveResult) + Sync),
+) -> Vec<SolveResult> {
+    let t1 = Instant::now();
+    let next_limit = || limits.next_problem(Instant::now().duration_since(t1));
+
+    let solve = |index, problem: Problem| {
+        let res = if escalation.as_ref().is_some_and(|i| i.skips(&problem)) {
+            (SolverOutcome::Skipped, problem, Duration::default())
+        } else {
+            on_start(index);
+            let res = solve_with_limit(solver, problem, next_limit());
+            if let Some(escalation) = &escalation {
+                escalation.record(&res);
+            }
+            res
+        };
+
+        on_result(index, &res);
+        res
+    };
+
+    if workers <= 1 {
+        return problems.into_iter()
+            .enumerate()
+            .map(|(index, i)| solve(index, i))
+            .collect()
+    }
+
+    let queue = Mutex::new(problems.into_iter().enumerate());
+    let results = Mutex::new(Vec::new());
+
+    std::thread::scope(|s| {
+        for _ in 0..workers {
+            s.spawn(|| loop {
+                let next = queue.lock().unwrap().next();
+                let (index, problem) = match next {
+                    Some(i) => i,
+                    None => break,
+                };
+
+                let res = solve(index, problem);
+                results.lock().unwrap().push((index, res));
+            });
+        }
+    });
+
+    let mut results = results.into_inner().unwrap();
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, res)| res).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::runner::{run_problems, TimeLimits};
+    use crate::solver::{SharedSolver, SolverOutcome};
+    use crate::problem::Problem;
+    use crate::solution::Solution;
+    use crate::testing::problem;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[test]
+    pub fn test_time_limit() {
+        fn solve(problem: Problem) -> Solution {
+            if problem.grid.width() > 1 {
+                std::thread::sleep(Duration::from_secs(2));
+            }
+
+            Solution { paths: vec![] }
+        }
+
+        let limits = TimeLimits {
+            problem: Some(Duration::from_millis(100)),
+            benchmark: None,
+        };
+        let solver: SharedSolver = Arc::new(solve);
+        let res = run_problems(&solver, vec![problem(2), problem(1)], limits, None, 1, &|_| {}, &|_, _| {});
+
+        assert_eq!(res[0].0, SolverOutcome::TimedOut);
+        assert!(res[0].2 >= Duration::from_millis(100));
+        assert_eq!(res[1].0, SolverOutcome::Solved(Solution { paths: vec![] }));
+
+        let limits = TimeLimits {
+            problem: None,
+            benchmark: Some(Duration::from_millis(100)),
+        };
+        let res = run_problems(&solver, vec![problem(2), problem(1)], limits, None, 1, &|_| {}, &|_, _| {});
+
+        assert_eq!(res[0].0, SolverOutcome::TimedOut);
+        assert_eq!(res[1].0, SolverOutcome::TimedOut);
+    }
+
+    #[test]
+    pub fn test_parallel_order() {
+        fn solve(problem: Problem) -> Solution {
+            let width = problem.grid.width();
+            std::thread::sleep(Duration::from_millis(50 * width as u64));
+
+            Solution { paths: vec![vec![]; width] }
+        }
+
+        let problems: Vec<_> = (1..=6).rev().map(problem).collect();
+
+        let solver: SharedSolver = Arc::new(solve);
+        let res = run_problems(&solver, problems.clone(), TimeLimits::default(), None, 3, &|_| {}, &|_, _| {});
+
+        assert_eq!(res.len(), problems.len());
+        for ((solution, problem, duration), expected) in res.iter().zip(&problems) {
+            assert_eq!(problem, expected);
+            assert_eq!(solution.solution().unwrap().paths.len(), expected.grid.width());
+            assert!(*duration >= Duration::from_millis(50 * expected.grid.width() as u64));
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use crate::coordinate::Coordinate;
+use serde::{Serialize, Deserialize};
 
-#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Hash)]
 pub struct Solution {
     pub paths: Vec<Vec<Coordinate>>,
 }
@@ -0,0 +1,108 @@
+use crate::problem::Problem;
+use crate::solution::Solution;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::sync::Arc;
+
+/// What a [`Solver`] made of a single problem.
+///
+/// Only the solution of a solved problem is sent to the server; for any other outcome the
+/// solution is sent as `null`. **Experimental:** that the server takes `null` for a problem
+/// which wasn't solved isn't confirmed against its api.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum SolverOutcome {
+    Solved(Solution),
+    /// The solver found that the problem has no solution.
+    Unsolvable,
+    /// The solver gave up, or didn't return within the time limit.
+    TimedOut,
+    /// The solver failed, for the reason in the message.
+    Error(String),
+    /// The problem wasn't given to the solver, because it kept failing on problems with
+    /// fewer agents. See [`MapfBenchmarkerBuilder::with_failure_cutoff`](crate::MapfBenchmarkerBuilder::with_failure_cutoff).
+    Skipped,
+}
+
+impl SolverOutcome {
+    pub fn solution(&self) -> Option<&Solution> {
+        match self {
+            Self::Solved(solution) => Some(solution),
+            _ => None,
+        }
+    }
+
+    pub fn is_solved(&self) -> bool {
+        matches!(self, Self::Solved(_))
+    }
+}
+
+impl From<Solution> for SolverOutcome {
+    fn from(solution: Solution) -> Self {
+        Self::Solved(solution)
+    }
+}
+
+/// `None` means the problem is unsolvable.
+impl From<Option<Solution>> for SolverOutcome {
+    fn from(solution: Option<Solution>) -> Self {
+        solution.map_or(Self::Unsolvable, Self::Solved)
+    }
+}
+
+impl<E: Display> From<Result<Solution, E>> for SolverOutcome {
+    fn from(solution: Result<Solution, E>) -> Self {
+        solution.map_or_else(|e| Self::Error(e.to_string()), Self::Solved)
+    }
+}
+
+/// A MAPF algorithm, solving the problems of a benchmark. A solver may be called from
+/// several threads at once (see [`MapfBenchmarkerBuilder::with_workers`](crate::MapfBenchmarkerBuilder::with_workers)),
+/// so any state it keeps between problems has to be behind a lock or atomic.
+///
+/// Closures and functions taking a [`Problem`] are solvers, when they return a [`SolverOutcome`]
+/// or anything which converts into one: a [`Solution`], an `Option<Solution>` or a
+/// `Result<Solution, E>`.
+pub trait Solver: Send + Sync {
+    fn solve(&self, problem: Problem) -> SolverOutcome;
+}
+
+impl<F: Fn(Problem) -> R + Send + Sync, R: Into<SolverOutcome>> Solver for F {
+    fn solve(&self, problem: Problem) -> SolverOutcome {
+        self(problem).into()
+    }
+}
+
+pub(crate) type SharedSolver = Arc<dyn Solver>;
+
+#[cfg(test)]
+mod tests {
+    use crate::problem::Problem;
+    use crate::solution::Solution;
+    use crate::solver::{SharedSolver, SolverOutcome};
+    use crate::testing::problem;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    pub fn test_closure_solver() {
+        let solved = Arc::new(AtomicUsize::new(0));
+        let counter = solved.clone();
+        let solver: SharedSolver = Arc::new(move |problem: Problem| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Solution { paths: vec![vec![]; problem.grid.width()] }
+        });
+        assert_eq!(solver.solve(problem(2)).solution().unwrap().paths.len(), 2);
+        solver.solve(problem(1));
+        assert_eq!(solved.load(Ordering::SeqCst), 2);
+
+        let solver: SharedSolver = Arc::new(|problem: Problem| match problem.grid.width() {
+            1 => Err("too narrow"),
+            _ => Ok(Solution { paths: vec![] }),
+        });
+        assert_eq!(solver.solve(problem(1)), SolverOutcome::Error("too narrow".to_string()));
+        assert!(solver.solve(problem(2)).is_solved());
+
+        let solver: SharedSolver = Arc::new(|_: Problem| None::<Solution>);
+        assert_eq!(solver.solve(problem(1)), SolverOutcome::Unsolvable);
+    }
+}
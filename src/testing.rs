@@ -0,0 +1,49 @@
+use crate::grid::Grid;
+use crate::problem::Problem;
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
+/// A problem without agents on an empty grid of `width` by 1, for tests which only need
+/// problems telling apart.
+pub fn problem(width: usize) -> Problem {
+    Problem {
+        grid: Grid::from_int_vecs(width, 1, vec![vec![0; width]]),
+        starts: vec![],
+        goals: vec![],
+    }
+}
+
+/// A path `mapfm-<name>-<pid>` in the temp dir, which is removed with everything under it
+/// when dropped, so also when the test using it fails.
+pub struct TempPath(PathBuf);
+
+impl TempPath {
+    pub fn new(name: &str) -> Self {
+        Self(std::env::temp_dir().join(format!("mapfm-{}-{}", name, std::process::id())))
+    }
+}
+
+impl Deref for TempPath {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for TempPath {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        // nothing may have been written to it, or the test removed it already
+        let _ = match self.0.is_dir() {
+            true => fs::remove_dir_all(&self.0),
+            false => fs::remove_file(&self.0),
+        };
+    }
+}
@@ -0,0 +1,115 @@
+use crate::client::MapfmClientError;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// The environment variable [`Token::resolve`] reads the token from.
+pub const TOKEN_ENV: &str = "MAPFM_TOKEN";
+
+/// The token to authenticate with the server. It is never shown: not in `Debug`
+/// output, and not in errors (it is removed from messages sent back by the server).
+#[derive(Clone, Eq, PartialEq)]
+pub struct Token(String);
+
+impl Token {
+    pub fn new(token: impl Into<String>) -> Self {
+        Self(token.into())
+    }
+
+    /// The token in the [`TOKEN_ENV`] environment variable, if it is set.
+    pub fn from_env() -> Option<Self> {
+        std::env::var(TOKEN_ENV).ok()
+            .map(|i| i.trim().to_string())
+            .filter(|i| !i.is_empty())
+            .map(Self)
+    }
+
+    /// Reads the token from a file, ignoring surrounding whitespace.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, MapfmClientError> {
+        let token = std::fs::read_to_string(path).map_err(MapfmClientError::TokenFile)?;
+
+        match token.trim() {
+            "" => Err(MapfmClientError::MissingToken),
+            token => Ok(Self::new(token)),
+        }
+    }
+
+    /// Where the per-user config file with the token is: `mapfm/token` in
+    /// `$XDG_CONFIG_HOME` or `~/.config` (`%APPDATA%` on windows).
+    pub fn config_path() -> Option<PathBuf> {
+        let config = std::env::var_os("XDG_CONFIG_HOME")
+            .or_else(|| std::env::var_os("APPDATA"))
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|i| Path::new(&i).join(".config")))?;
+
+        Some(config.join("mapfm").join("token"))
+    }
+
+    /// Takes the first token there is out of `explicit`, the [`TOKEN_ENV`]
+    /// environment variable and the [config file](Self::config_path).
+    pub fn resolve(explicit: Option<&str>) -> Result<Self, MapfmClientError> {
+        if let Some(token) = explicit {
+            return Ok(Self::new(token));
+        }
+
+        if let Some(token) = Self::from_env() {
+            return Ok(token);
+        }
+
+        match Self::config_path() {
+            Some(path) if path.exists() => Self::from_file(path),
+            _ => Err(MapfmClientError::MissingToken),
+        }
+    }
+
+    pub(crate) fn expose(&self) -> &str {
+        &self.0
+    }
+
+    /// `message` with every occurrence of the token blanked out.
+    pub(crate) fn redact(&self, message: String) -> String {
+        if self.0.is_empty() {
+            return message;
+        }
+
+        message.replace(&self.0, "***")
+    }
+}
+
+impl fmt::Debug for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Token(***)")
+    }
+}
+
+impl From<&str> for Token {
+    fn from(token: &str) -> Self {
+        Self::new(token)
+    }
+}
+
+impl From<String> for Token {
+    fn from(token: String) -> Self {
+        Self::new(token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::TempPath;
+    use crate::token::Token;
+    use std::fs;
+
+    #[test]
+    pub fn test_token() {
+        let token = Token::resolve(Some("secret")).unwrap();
+        assert_eq!(token.expose(), "secret");
+        assert!(!format!("{:?}", token).contains("secret"));
+        assert_eq!(token.redact("invalid token secret".to_string()), "invalid token ***");
+
+        let path = TempPath::new("token");
+        fs::write(&path, "secret\n").unwrap();
+        assert_eq!(Token::from_file(&path).unwrap(), token);
+        fs::write(&path, "  ").unwrap();
+        assert!(Token::from_file(&path).is_err());
+    }
+}
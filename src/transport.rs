@@ -0,0 +1,47 @@
+use crate::client::{BenchmarkDescriptor, MapfmClientError};
+use crate::problem::Problem;
+use crate::runner::SolveResult;
+
+/// What is being benchmarked, passed to a [`ProblemSource`] whenever problems are requested.
+#[derive(Clone, Debug)]
+pub struct BenchmarkRequest<'a> {
+    pub descriptor: &'a BenchmarkDescriptor,
+    pub algorithm_name: &'a str,
+    pub version: &'a str,
+    pub debug: bool,
+}
+
+/// Where a [`MapfBenchmarker`](crate::MapfBenchmarker) gets the problems it solves from.
+pub trait ProblemSource {
+    /// Checks whether the problems of `request` can be requested at all (for example,
+    /// whether the credentials are valid), without starting an attempt. Only called when
+    /// asked for, see [`MapfBenchmarker::validate_token`](crate::MapfBenchmarker::validate_token).
+    /// Succeeds by default.
+    fn validate(&self, _request: &BenchmarkRequest) -> Result<(), MapfmClientError> {
+        Ok(())
+    }
+
+    /// The problems of a benchmark, without starting an attempt.
+    fn get_benchmark(&self, request: &BenchmarkRequest) -> Result<Vec<Problem>, MapfmClientError>;
+
+    /// Starts a new attempt at a benchmark, returning its problems and the id of the attempt.
+    fn start_attempt(&self, request: &BenchmarkRequest) -> Result<(Vec<Problem>, i64), MapfmClientError>;
+}
+
+/// Where a [`MapfBenchmarker`](crate::MapfBenchmarker) sends the solutions of an attempt.
+pub trait SolutionSink {
+    /// Submits the results for all problems of an attempt, in the order of the problems.
+    fn submit_solutions(&self, descriptor: &BenchmarkDescriptor, attempt_id: i64, solutions: &[SolveResult]) -> Result<(), MapfmClientError>;
+
+    /// Submits the results for some of the problems of an attempt while it is still
+    /// running, each together with the index of its problem in the attempt.
+    fn submit_batch(&self, descriptor: &BenchmarkDescriptor, attempt_id: i64, batch: &[(usize, SolveResult)]) -> Result<(), MapfmClientError>;
+}
+
+/// A [`ProblemSource`] and [`SolutionSink`] in one, which is what a
+/// [`MapfBenchmarker`](crate::MapfBenchmarker) runs against. Streamed solutions are
+/// submitted from a thread of their own, while problems are fetched and solved on others,
+/// so it has to be [`Sync`].
+pub trait Transport: ProblemSource + SolutionSink + Sync {}
+
+impl<T: ProblemSource + SolutionSink + Sync> Transport for T {}